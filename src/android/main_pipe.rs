@@ -57,6 +57,7 @@ impl<'a> MainPipe<'a> {
             headers,
             on_webview_created,
             autoplay,
+            minimum_font_size,
             user_agent,
             initialization_scripts,
             id,
@@ -100,6 +101,25 @@ impl<'a> MainPipe<'a> {
             .env
             .call_method(&webview, "setAutoPlay", "(Z)V", &[autoplay.into()])?;
 
+          // set minimum font size
+          if let Some(size) = minimum_font_size {
+            let settings = self
+              .env
+              .call_method(
+                &webview,
+                "getSettings",
+                "()Landroid/webkit/WebSettings;",
+                &[],
+              )?
+              .l()?;
+            self.env.call_method(
+              &settings,
+              "setMinimumFontSize",
+              "(I)V",
+              &[(size as i32).into()],
+            )?;
+          }
+
           // set user-agent
           if let Some(user_agent) = user_agent {
             let user_agent = self.env.new_string(user_agent)?;
@@ -298,6 +318,15 @@ impl<'a> MainPipe<'a> {
             load_url(&mut self.env, webview.as_obj(), &url, headers, false)?;
           }
         }
+        WebViewMessage::ReloadBypassingCache => {
+          if let Some(webview) = &self.webview {
+            // `WebView.reload` has no cache option, so empty the in-memory cache first
+            self
+              .env
+              .call_method(webview, "clearCache", "(Z)V", &[false.into()])?;
+            self.env.call_method(webview, "reload", "()V", &[])?;
+          }
+        }
         WebViewMessage::ClearAllBrowsingData => {
           if let Some(webview) = &self.webview {
             self
@@ -412,6 +441,7 @@ pub(crate) enum WebViewMessage {
   Jni(Box<dyn FnOnce(&mut JNIEnv, &JObject, &JObject) + Send>),
   LoadUrl(String, Option<http::HeaderMap>),
   LoadHtml(String),
+  ReloadBypassingCache,
   ClearAllBrowsingData,
 }
 
@@ -425,6 +455,7 @@ pub(crate) struct CreateWebViewAttributes {
   pub background_color: Option<RGBA>,
   pub headers: Option<http::HeaderMap>,
   pub autoplay: bool,
+  pub minimum_font_size: Option<u32>,
   pub on_webview_created: Option<Box<dyn Fn(super::Context) -> JniResult<()> + Send>>,
   pub user_agent: Option<String>,
   pub initialization_scripts: Vec<String>,
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: MIT
 
 use super::{PageLoadEvent, WebViewAttributes, RGBA};
-use crate::{RequestAsyncResponder, Result};
+use crate::{CachePolicy, RequestAsyncResponder, Result};
 use base64::{engine::general_purpose, Engine};
 use crossbeam_channel::*;
 use html5ever::{interface::QualName, namespace_url, ns, tendril::TendrilSink, LocalName};
@@ -158,6 +158,7 @@ impl InnerWebView {
       transparent,
       headers,
       autoplay,
+      minimum_font_size,
       user_agent,
       ..
     } = attributes;
@@ -201,6 +202,7 @@ impl InnerWebView {
       headers,
       on_webview_created,
       autoplay,
+      minimum_font_size,
       user_agent,
       initialization_scripts: initialization_scripts.clone(),
     }));
@@ -317,6 +319,37 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn edit(&self, command: crate::EditCommand) -> Result<()> {
+    self.eval(
+      command.script(),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn save_page<F>(
+    &self,
+    _path: &std::path::Path,
+    _format: crate::SaveFormat,
+    _handler: F,
+  ) -> crate::Result<()>
+  where
+    F: FnOnce(crate::Result<()>) + 'static,
+  {
+    Err(crate::Error::UnsupportedSaveFormat)
+  }
+
+  pub fn back_forward_list(&self) -> crate::Result<crate::BackForwardList> {
+    Ok(Default::default())
+  }
+
+  pub fn serialize_session(&self) -> crate::Result<Vec<u8>> {
+    Err(crate::Error::UnsupportedSessionRestore)
+  }
+
+  pub fn restore_session(&self, _session: &[u8]) -> crate::Result<()> {
+    Err(crate::Error::UnsupportedSessionRestore)
+  }
+
   pub fn id(&self) -> crate::WebViewId {
     &self.id
   }
@@ -365,11 +398,27 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn load_url_with(&self, url: &str, cache_policy: CachePolicy) -> Result<()> {
+    match cache_policy.request_headers() {
+      Some(headers) => self.load_url_with_headers(url, headers),
+      None => self.load_url(url),
+    }
+  }
+
+  pub fn reload_bypassing_cache(&self) -> Result<()> {
+    MainPipe::send(WebViewMessage::ReloadBypassingCache);
+    Ok(())
+  }
+
   pub fn load_html(&self, html: &str) -> Result<()> {
     MainPipe::send(WebViewMessage::LoadHtml(html.to_string()));
     Ok(())
   }
 
+  pub fn close(&self) -> Result<()> {
+    Ok(())
+  }
+
   pub fn clear_all_browsing_data(&self) -> Result<()> {
     MainPipe::send(WebViewMessage::ClearAllBrowsingData);
     Ok(())
@@ -399,6 +448,16 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn raise(&self) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
+  pub fn lower(&self) -> Result<()> {
+    // Unsupported
+    Ok(())
+  }
+
   pub fn focus(&self) -> Result<()> {
     // Unsupported
     Ok(())
@@ -430,6 +489,8 @@ pub fn platform_webview_version() -> Result<String> {
   rx.recv().unwrap()
 }
 
+pub fn open_with_default_handler(_uri: &str) {}
+
 fn with_html_head<F: FnOnce(&NodeRef)>(document: &mut NodeRef, f: F) {
   if let Ok(ref node) = document.select_first("head") {
     f(node.as_node())
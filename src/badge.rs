@@ -0,0 +1,84 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! `navigator.setAppBadge()` for the host, see [`WebViewBuilder::with_badge_handler`](crate::WebViewBuilder::with_badge_handler).
+
+use http::Request;
+
+use crate::ipc;
+
+pub(crate) const BADGE_SCRIPT: &str = r#"(function () {
+  const prefix = '__wry__:badge:';
+  function post(contents) {
+    window.ipc.postMessage(prefix + contents);
+    return Promise.resolve();
+  }
+  Object.defineProperty(navigator, 'setAppBadge', {
+    value: function (contents) {
+      if (contents === undefined) return post('flag');
+      const count = Number(contents);
+      if (!Number.isSafeInteger(count) || count < 0) {
+        return Promise.reject(new TypeError('The badge contents must be a non-negative integer'));
+      }
+      return post(String(count));
+    },
+  });
+  Object.defineProperty(navigator, 'clearAppBadge', {
+    value: function () { return post('0') },
+  });
+})();"#;
+
+/// The application badge set by the page with `navigator.setAppBadge()`.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppBadge {
+  /// The badge was cleared, with `navigator.clearAppBadge()` or a count of `0`.
+  Cleared,
+  /// The badge is set without a count, usually shown as a dot.
+  Flag,
+  /// The badge shows a count.
+  Count(u64),
+}
+
+/// Wraps the IPC handler so that badge messages go to `badge_handler`
+/// and everything else is forwarded to `handler`.
+pub(crate) fn wrap_ipc_handler(
+  badge_handler: Box<dyn Fn(AppBadge)>,
+  handler: Option<Box<dyn Fn(Request<String>)>>,
+) -> Box<dyn Fn(Request<String>)> {
+  ipc::wrap_internal_route(
+    "badge",
+    move |contents| match parse(contents) {
+      Some(badge) => {
+        badge_handler(badge);
+        true
+      }
+      None => false,
+    },
+    handler,
+  )
+}
+
+fn parse(contents: &str) -> Option<AppBadge> {
+  match contents {
+    "flag" => Some(AppBadge::Flag),
+    _ => match contents.parse().ok()? {
+      0 => Some(AppBadge::Cleared),
+      count => Some(AppBadge::Count(count)),
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_badge_messages() {
+    assert_eq!(parse("flag"), Some(AppBadge::Flag));
+    assert_eq!(parse("0"), Some(AppBadge::Cleared));
+    assert_eq!(parse("12"), Some(AppBadge::Count(12)));
+    assert_eq!(parse("-1"), None);
+  }
+}
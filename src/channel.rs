@@ -0,0 +1,212 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use http::Request;
+
+use crate::{ipc, Error, Result, WebView};
+
+const CHANNELS_SCRIPT: &str = r#"(function () {
+  if (window.wryChannels) return;
+  const prefix = '__wry__:channel:';
+  const channels = Object.create(null);
+  function get(name) {
+    let c = channels[name];
+    if (!c) {
+      c = channels[name] = { credits: 0, buffer: [] };
+      c.port = {
+        onmessage: null,
+        get bufferedAmount() { return c.buffer.length },
+        postMessage(message) {
+          c.buffer.push(String(message));
+          flush(name, c);
+          return c.buffer.length === 0;
+        },
+      };
+    }
+    return c;
+  }
+  function flush(name, c) {
+    while (c.credits > 0 && c.buffer.length > 0) {
+      c.credits--;
+      window.ipc.postMessage(prefix + name + ':' + c.buffer.shift());
+    }
+  }
+  Object.defineProperty(window, 'wryChannels', {
+    value: Object.freeze({
+      open: function (name) { return get(name).port },
+      _credit: function (name, n) { const c = get(name); c.credits += n; flush(name, c) },
+      _receive: function (name, data) {
+        const port = get(name).port;
+        if (typeof port.onmessage === 'function') port.onmessage({ data });
+      },
+    }),
+  });
+})();"#;
+
+/// A named, bidirectional message stream between Rust and the webview.
+///
+/// Unlike the IPC handler, messages are queued per channel and pulled with [`Channel::recv`],
+/// and the JavaScript side is flow controlled: it can only have `capacity` messages that were
+/// not yet drained in flight. Messages posted past that limit are buffered in the page until
+/// Rust catches up.
+///
+/// Register the channel with [`WebViewBuilder::with_channel`](crate::WebViewBuilder::with_channel),
+/// then open it from JavaScript:
+///
+/// ```js
+/// const port = window.wryChannels.open('telemetry');
+/// port.onmessage = (event) => console.log(event.data);
+/// // returns `false` and buffers the message when Rust is lagging behind
+/// port.postMessage('frame-time:16');
+/// console.log(port.bufferedAmount);
+/// ```
+#[derive(Clone)]
+pub struct Channel {
+  inner: Rc<ChannelInner>,
+}
+
+struct ChannelInner {
+  name: String,
+  capacity: usize,
+  queue: RefCell<VecDeque<String>>,
+}
+
+impl Channel {
+  /// Create a new channel with the given name and the maximum number of
+  /// undrained messages the JavaScript side is allowed to send.
+  ///
+  /// Returns [`Error::InvalidChannelName`] if `name` contains a `:`, and
+  /// [`Error::InvalidChannelCapacity`] if `capacity` is zero.
+  pub fn new(name: impl Into<String>, capacity: usize) -> Result<Self> {
+    let name = name.into();
+    if name.contains(':') {
+      return Err(Error::InvalidChannelName(name));
+    }
+    if capacity == 0 {
+      return Err(Error::InvalidChannelCapacity);
+    }
+    Ok(Self {
+      inner: Rc::new(ChannelInner {
+        name,
+        capacity,
+        queue: Default::default(),
+      }),
+    })
+  }
+
+  /// The name used to open this channel from JavaScript.
+  pub fn name(&self) -> &str {
+    &self.inner.name
+  }
+
+  /// Send a message to the `onmessage` handler of the JavaScript side of this channel.
+  pub fn send(&self, webview: &WebView, message: &str) -> Result<()> {
+    webview.evaluate_script(&format!(
+      "window.wryChannels._receive({}, {})",
+      js_string(self.name()),
+      js_string(message)
+    ))
+  }
+
+  /// Take all the messages received from JavaScript so far, and allow the
+  /// JavaScript side to send as many new messages.
+  pub fn recv(&self, webview: &WebView) -> Result<Vec<String>> {
+    let count = self.inner.queue.borrow().len();
+    if count > 0 {
+      webview.evaluate_script(&format!(
+        "window.wryChannels._credit({}, {count})",
+        js_string(self.name())
+      ))?;
+    }
+    Ok(self.inner.queue.borrow_mut().drain(..count).collect())
+  }
+}
+
+/// The script that sets up `window.wryChannels` and grants the initial credits of `channels`.
+pub(crate) fn init_script(channels: &[Channel]) -> String {
+  let mut script = CHANNELS_SCRIPT.to_string();
+  for channel in channels {
+    script.push_str(&format!(
+      "window.wryChannels._credit({}, {});",
+      js_string(channel.name()),
+      channel.inner.capacity
+    ));
+  }
+  script
+}
+
+/// Wraps the IPC handler so that channel messages are queued on their [`Channel`]
+/// and everything else, including messages for channels that aren't registered,
+/// is forwarded to `handler`.
+pub(crate) fn wrap_ipc_handler(
+  channels: Vec<Channel>,
+  handler: Option<Box<dyn Fn(Request<String>)>>,
+) -> Box<dyn Fn(Request<String>)> {
+  ipc::wrap_internal_route(
+    "channel",
+    move |payload| {
+      let Some((name, message)) = payload.split_once(':') else {
+        return false;
+      };
+      let Some(channel) = channels.iter().find(|c| c.name() == name) else {
+        return false;
+      };
+      channel
+        .inner
+        .queue
+        .borrow_mut()
+        .push_back(message.to_string());
+      true
+    },
+    handler,
+  )
+}
+
+/// Encode `s` as a JavaScript string literal.
+pub(crate) fn js_string(s: &str) -> String {
+  let mut out = String::with_capacity(s.len() + 2);
+  out.push('"');
+  for c in s.chars() {
+    match c {
+      '"' => out.push_str("\\\""),
+      '\\' => out.push_str("\\\\"),
+      '\n' => out.push_str("\\n"),
+      '\r' => out.push_str("\\r"),
+      '\u{2028}' => out.push_str("\\u2028"),
+      '\u{2029}' => out.push_str("\\u2029"),
+      c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+      c => out.push(c),
+    }
+  }
+  out.push('"');
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn encodes_js_string_literals() {
+    assert_eq!(js_string("hello"), r#""hello""#);
+    assert_eq!(js_string("a\"b\\c"), r#""a\"b\\c""#);
+    assert_eq!(js_string("line\nbreak\u{2028}"), r#""line\nbreak\u2028""#);
+    assert_eq!(js_string("\u{0}"), r#""\u0000""#);
+  }
+
+  #[test]
+  fn rejects_invalid_channels() {
+    assert!(matches!(
+      Channel::new("a:b", 1),
+      Err(Error::InvalidChannelName(name)) if name == "a:b"
+    ));
+    assert!(matches!(
+      Channel::new("telemetry", 0),
+      Err(Error::InvalidChannelCapacity)
+    ));
+    assert!(Channel::new("telemetry", 1).is_ok());
+  }
+}
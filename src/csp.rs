@@ -0,0 +1,97 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A default Content Security Policy, see [`WebViewBuilder::with_content_security_policy`](crate::WebViewBuilder::with_content_security_policy).
+
+use std::borrow::Cow;
+
+use http::{header::CONTENT_SECURITY_POLICY, HeaderValue, Request, Response};
+
+use crate::{RequestAsyncResponder, WebViewId};
+
+/// Wraps a custom protocol handler so that responses without a `Content-Security-Policy`
+/// header get `csp`.
+pub(crate) fn wrap_protocol(
+  csp: HeaderValue,
+  handler: Box<dyn Fn(WebViewId, Request<Vec<u8>>, RequestAsyncResponder)>,
+) -> Box<dyn Fn(WebViewId, Request<Vec<u8>>, RequestAsyncResponder)> {
+  Box::new(move |id, request, responder| {
+    let csp = csp.clone();
+    let responder = RequestAsyncResponder {
+      responder: Box::new(move |mut response: Response<Cow<'static, [u8]>>| {
+        response
+          .headers_mut()
+          .entry(CONTENT_SECURITY_POLICY)
+          .or_insert(csp);
+        (responder.responder)(response);
+      }),
+    };
+    handler(id, request, responder)
+  })
+}
+
+/// Insert a `<meta http-equiv="Content-Security-Policy">` tag with `csp` at the start of `html`,
+/// after its doctype if it has one.
+///
+/// The tag isn't put in the `<head>`: in untrusted html, the first `<head` can be hidden in a
+/// comment or an attribute, and the policy would be ignored. The parser moves a leading `<meta>`
+/// into the head anyway.
+pub(crate) fn inject_meta(csp: &str, html: &str) -> String {
+  let meta = format!(
+    r#"<meta http-equiv="Content-Security-Policy" content="{}">"#,
+    escape_attribute(csp)
+  );
+
+  let start = html.len() - html.trim_start().len();
+  let doctype_end = html[start..]
+    .get(..9)
+    .filter(|prefix| prefix.eq_ignore_ascii_case("<!doctype"))
+    .and_then(|_| html[start..].find('>'))
+    .map(|i| start + i + 1);
+  match doctype_end {
+    Some(i) => format!("{}{meta}{}", &html[..i], &html[i..]),
+    None => format!("{meta}{html}"),
+  }
+}
+
+fn escape_attribute(s: &str) -> String {
+  s.replace('&', "&amp;")
+    .replace('"', "&quot;")
+    .replace('<', "&lt;")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn injects_meta_tag() {
+    let meta = r#"<meta http-equiv="Content-Security-Policy" content="default-src 'self'">"#;
+    assert_eq!(
+      inject_meta(
+        "default-src 'self'",
+        "<html><HEAD lang=en><title>a</title></HEAD></html>"
+      ),
+      format!("{meta}<html><HEAD lang=en><title>a</title></HEAD></html>")
+    );
+    assert_eq!(
+      inject_meta("default-src 'self'", "\n<!DOCTYPE html><html></html>"),
+      format!("\n<!DOCTYPE html>{meta}<html></html>")
+    );
+    assert_eq!(
+      inject_meta("default-src 'self'", "<header>hi</header>"),
+      format!("{meta}<header>hi</header>")
+    );
+  }
+
+  #[test]
+  fn injects_meta_tag_before_hidden_heads() {
+    let meta = r#"<meta http-equiv="Content-Security-Policy" content="default-src 'none'">"#;
+    let html = "<!-- <head> --><img src=https://t.example/p>";
+    assert_eq!(
+      inject_meta("default-src 'none'", html),
+      format!("{meta}{html}")
+    );
+  }
+}
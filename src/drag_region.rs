@@ -0,0 +1,71 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Moving the window from `data-wry-drag-region` elements, see [`WebViewBuilder::with_drag_regions`](crate::WebViewBuilder::with_drag_regions).
+
+use std::rc::Rc;
+
+use http::Request;
+use once_cell::unsync::OnceCell;
+
+use crate::ipc;
+
+pub(crate) const DRAG_REGION_SCRIPT: &str = r#"(function () {
+  const prefix = '__wry__:drag-region:';
+  document.addEventListener('mousedown', function (e) {
+    if (e.button !== 0 || !(e.target instanceof Element) || !e.target.hasAttribute('data-wry-drag-region')) return;
+    e.preventDefault();
+    window.ipc.postMessage(prefix + (e.detail === 2 ? 'toggle-maximize' : 'drag'));
+  });
+})();"#;
+
+/// Marks the drag regions with `app-region: drag`, the WebView2 runtimes that support
+/// non-client regions then move the window themselves and the page never sees the presses.
+#[cfg(windows)]
+pub(crate) const DRAG_REGION_STYLE_SCRIPT: &str = r#"(function () {
+  function inject() {
+    const style = document.createElement('style');
+    style.textContent = '[data-wry-drag-region] { app-region: drag } '
+      + '[data-wry-drag-region] *:not([data-wry-drag-region]) { app-region: no-drag }';
+    (document.head || document.documentElement).appendChild(style);
+  }
+  if (document.readyState === 'loading') document.addEventListener('DOMContentLoaded', inject);
+  else inject();
+})();"#;
+
+/// What a press on a drag region asks the window to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DragRegionAction {
+  /// Start moving the window with the mouse.
+  Drag,
+  /// Maximize the window, or restore it if it's already maximized.
+  ToggleMaximize,
+}
+
+/// The window side of the drag regions, set by the backend once the webview exists
+/// since the IPC handler is wrapped before that.
+pub(crate) type DragRegionTarget = Rc<OnceCell<Box<dyn Fn(DragRegionAction)>>>;
+
+/// Wraps the IPC handler so that drag region messages go to `target`
+/// and everything else is forwarded to `handler`.
+pub(crate) fn wrap_ipc_handler(
+  target: DragRegionTarget,
+  handler: Option<Box<dyn Fn(Request<String>)>>,
+) -> Box<dyn Fn(Request<String>)> {
+  ipc::wrap_internal_route(
+    "drag-region",
+    move |action| {
+      let action = match action {
+        "drag" => DragRegionAction::Drag,
+        "toggle-maximize" => DragRegionAction::ToggleMaximize,
+        _ => return false,
+      };
+      if let Some(target) = target.get() {
+        target(action);
+      }
+      true
+    },
+    handler,
+  )
+}
@@ -0,0 +1,30 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Operating on the page selection, see [`WebView::selected_text`](crate::WebView::selected_text).
+
+pub(crate) const SELECTED_TEXT_SCRIPT: &str = "window.getSelection().toString()";
+pub(crate) const CLEAR_SELECTION_SCRIPT: &str = "window.getSelection().removeAllRanges()";
+
+/// An editing command that applies to the page selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EditCommand {
+  Copy,
+  Cut,
+  Paste,
+  SelectAll,
+}
+
+impl EditCommand {
+  /// The `document.execCommand` fallback, for the engines without native editing commands.
+  #[cfg(any(target_os = "windows", target_os = "android"))]
+  pub(crate) fn script(self) -> &'static str {
+    match self {
+      Self::Copy => "document.execCommand('copy')",
+      Self::Cut => "document.execCommand('cut')",
+      Self::Paste => "document.execCommand('paste')",
+      Self::SelectAll => "document.execCommand('selectAll')",
+    }
+  }
+}
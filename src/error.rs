@@ -50,6 +50,10 @@ pub enum Error {
   WindowHandleError(#[from] raw_window_handle::HandleError),
   #[error("the window handle kind is not supported")]
   UnsupportedWindowHandle,
+  #[error("the save format is not supported on this platform")]
+  UnsupportedSaveFormat,
+  #[error("saving and restoring the session is not supported on this platform")]
+  UnsupportedSessionRestore,
   #[error(transparent)]
   Utf8Error(#[from] std::str::Utf8Error),
   #[cfg(target_os = "android")]
@@ -65,6 +69,20 @@ pub enum Error {
   DuplicateCustomProtocol(String),
   #[error("Duplicate custom protocol registered on the same web context on Linux: {0}")]
   ContextDuplicateCustomProtocol(String),
+  #[error("Invalid custom protocol name: {0}, it must start with a letter followed by letters, digits, `+`, `-` or `.`")]
+  InvalidCustomProtocol(String),
+  #[error("Custom protocol name is reserved by the webview: {0}")]
+  ReservedCustomProtocol(String),
+  #[error("Failed to read the scroll position from the script result: {0}")]
+  InvalidScrollPosition(String),
+  #[error("The scroll position must be finite, got ({0}, {1})")]
+  NonFiniteScrollPosition(f64, f64),
+  #[error("Invalid channel name: {0:?}, it must not contain `:`")]
+  InvalidChannelName(String),
+  #[error("The channel capacity must be greater than zero")]
+  InvalidChannelCapacity,
+  #[error("Invalid host resolver rule entry: {0:?}, it must be non-empty without whitespace, quotes or commas")]
+  InvalidHostResolverRule(String),
   #[error(transparent)]
   #[cfg(any(target_os = "macos", target_os = "ios"))]
   UrlPrase(#[from] url::ParseError),
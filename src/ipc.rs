@@ -0,0 +1,133 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Restricting the IPC bridge to a set of origins, see [`WebViewBuilder::with_ipc_origins`](crate::WebViewBuilder::with_ipc_origins).
+
+use http::{Request, Uri};
+
+use crate::channel::js_string;
+
+/// Namespace of the messages wry posts over the IPC transport for its own features,
+/// shaped like `__wry__:<kind>:<payload>`.
+pub(crate) const INTERNAL_MESSAGE_PREFIX: &str = "__wry__:";
+
+/// Guard the script that defines `window.ipc` so that it only runs on the allowed origins.
+pub(crate) fn init_script(origins: Option<&[String]>, script: &str) -> String {
+  match origins {
+    Some(origins) => {
+      let origins = origins
+        .iter()
+        .map(|o| js_string(o))
+        .collect::<Vec<_>>()
+        .join(",");
+      format!("if ([{origins}].includes(window.location.origin)) {{ {script} }}")
+    }
+    None => script.to_string(),
+  }
+}
+
+/// Wraps the IPC handler so that messages posted from other origins are dropped,
+/// even if the page reached the native transport without `window.ipc`.
+pub(crate) fn wrap_ipc_handler(
+  origins: Vec<String>,
+  handler: Box<dyn Fn(Request<String>)>,
+) -> Box<dyn Fn(Request<String>)> {
+  Box::new(move |request| {
+    let allowed = origin(request.uri()).is_some_and(|origin| origins.contains(&origin));
+    if allowed {
+      handler(request);
+    }
+  })
+}
+
+/// Wraps the IPC handler so that the internal messages of `kind` go to `route`.
+///
+/// Everything else is forwarded to `handler`, including the internal messages `route`
+/// returns `false` for, so a page message is never dropped just because of how it starts.
+pub(crate) fn wrap_internal_route(
+  kind: &'static str,
+  route: impl Fn(&str) -> bool + 'static,
+  handler: Option<Box<dyn Fn(Request<String>)>>,
+) -> Box<dyn Fn(Request<String>)> {
+  Box::new(move |request| {
+    let payload = request
+      .body()
+      .strip_prefix(INTERNAL_MESSAGE_PREFIX)
+      .and_then(|m| m.strip_prefix(kind))
+      .and_then(|m| m.strip_prefix(':'));
+    if payload.is_some_and(&route) {
+      return;
+    }
+
+    if let Some(handler) = &handler {
+      handler(request);
+    }
+  })
+}
+
+/// The serialized origin of `uri`, as in `window.location.origin`.
+fn origin(uri: &Uri) -> Option<String> {
+  Some(format!("{}://{}", uri.scheme_str()?, uri.authority()?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn serializes_origins() {
+    let origin = |uri: &str| origin(&uri.parse().unwrap());
+    assert_eq!(
+      origin("https://tauri.app/about?q=1#top").as_deref(),
+      Some("https://tauri.app")
+    );
+    assert_eq!(
+      origin("http://localhost:8080/").as_deref(),
+      Some("http://localhost:8080")
+    );
+    assert_eq!(origin("/relative/path"), None);
+  }
+
+  #[test]
+  fn forwards_unrouted_internal_messages() {
+    use std::{cell::RefCell, rc::Rc};
+
+    let routed = Rc::new(RefCell::new(Vec::new()));
+    let forwarded = Rc::new(RefCell::new(Vec::new()));
+    let handler = {
+      let routed = routed.clone();
+      let forwarded = forwarded.clone();
+      wrap_internal_route(
+        "badge",
+        move |payload| {
+          routed.borrow_mut().push(payload.to_string());
+          payload == "flag"
+        },
+        Some(Box::new(move |r: Request<String>| {
+          forwarded.borrow_mut().push(r.into_body())
+        })),
+      )
+    };
+
+    for body in [
+      "__wry__:badge:flag",
+      "__wry__:badge:nope",
+      "__wry__:badges:flag",
+      "__wry__:channel:a:b",
+      "hello",
+    ] {
+      handler(Request::new(body.to_string()));
+    }
+    assert_eq!(*routed.borrow(), ["flag", "nope"]);
+    assert_eq!(
+      *forwarded.borrow(),
+      [
+        "__wry__:badge:nope",
+        "__wry__:badges:flag",
+        "__wry__:channel:a:b",
+        "hello"
+      ]
+    );
+  }
+}
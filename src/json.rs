@@ -0,0 +1,94 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Reading the JSON strings returned by evaluated scripts and DevTools protocol methods.
+
+/// Decode the JSON string literal `json`, or `None` if it's anything else, like `null`.
+pub(crate) fn decode_string(json: &str) -> Option<String> {
+  match unescape(json.strip_prefix('"')?)? {
+    (value, "") => Some(value),
+    _ => None,
+  }
+}
+
+/// Extract and decode the string value of `key` in the flat JSON object `json`.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(crate) fn string_field(json: &str, key: &str) -> Option<String> {
+  let pattern = format!("\"{key}\":\"");
+  let start = json.find(&pattern)? + pattern.len();
+  unescape(&json[start..]).map(|(value, _)| value)
+}
+
+/// Unescape the string whose opening quote was just before `json`,
+/// returns its value and what follows the closing quote.
+fn unescape(json: &str) -> Option<(String, &str)> {
+  let mut chars = json.chars();
+  let mut value = String::with_capacity(json.len());
+  loop {
+    match chars.next()? {
+      '"' => return Some((value, chars.as_str())),
+      '\\' => match chars.next()? {
+        'n' => value.push('\n'),
+        'r' => value.push('\r'),
+        't' => value.push('\t'),
+        'b' => value.push('\u{8}'),
+        'f' => value.push('\u{c}'),
+        'u' => {
+          let high = hex4(&mut chars)?;
+          let c = if (0xD800..0xDC00).contains(&high) {
+            // a surrogate pair is encoded as two escapes
+            if chars.next()? != '\\' || chars.next()? != 'u' {
+              return None;
+            }
+            let low = hex4(&mut chars)?;
+            char::decode_utf16([high, low]).next()?.ok()?
+          } else {
+            char::from_u32(high as u32)?
+          };
+          value.push(c);
+        }
+        c => value.push(c),
+      },
+      c => value.push(c),
+    }
+  }
+}
+
+fn hex4(chars: &mut std::str::Chars) -> Option<u16> {
+  let digits = chars.by_ref().take(4).collect::<String>();
+  u16::from_str_radix(&digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn decodes_strings() {
+    assert_eq!(decode_string(r#""hello""#).as_deref(), Some("hello"));
+    assert_eq!(
+      decode_string(r#""a\"b\\c\nd\/""#).as_deref(),
+      Some("a\"b\\c\nd/")
+    );
+    assert_eq!(
+      decode_string(r#""\u00e9\ud83d\ude00""#).as_deref(),
+      Some("é😀")
+    );
+    assert_eq!(decode_string("null"), None);
+    assert_eq!(decode_string(r#""a"b""#), None);
+  }
+
+  #[test]
+  fn extracts_string_fields() {
+    let json = r#"{"format":"mhtml","data":"From: <Saved>\r\nline \"two\"","size":2}"#;
+    assert_eq!(
+      string_field(json, "data").as_deref(),
+      Some("From: <Saved>\r\nline \"two\"")
+    );
+    assert_eq!(string_field(json, "format").as_deref(), Some("mhtml"));
+    assert_eq!(string_field(json, "size"), None);
+    assert_eq!(string_field(json, "missing"), None);
+    assert_eq!(string_field(r#"{"data":"unterminated"#, "data"), None);
+  }
+}
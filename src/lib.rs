@@ -194,7 +194,17 @@
 // #[macro_use]
 // extern crate objc;
 
+mod badge;
+mod channel;
+mod csp;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod drag_region;
+mod edit;
 mod error;
+mod ipc;
+mod json;
+mod navigation;
+mod plugin;
 mod proxy;
 #[cfg(any(target_os = "macos", target_os = "android", target_os = "ios"))]
 mod util;
@@ -238,22 +248,36 @@ pub use wkwebview::{PrintMargin, PrintOptions, WryWebView};
 #[cfg(target_os = "windows")]
 pub(crate) mod webview2;
 #[cfg(target_os = "windows")]
-pub use self::webview2::ScrollBarStyle;
-#[cfg(target_os = "windows")]
 use self::webview2::*;
 #[cfg(target_os = "windows")]
-use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Controller;
-
-use std::{borrow::Cow, collections::HashMap, path::PathBuf, rc::Rc};
-
+pub use self::webview2::{
+  AcceleratorKeyEvent, HostResourceAccessKind, ProcessKind, ProcessMemoryUsage,
+  ReleaseChannelPreference, ScrollBarStyle,
+};
+#[cfg(target_os = "windows")]
+use webview2_com::Microsoft::Web::WebView2::Win32::{
+  ICoreWebView2, ICoreWebView2Controller, ICoreWebView2Environment,
+};
+
+use std::{
+  borrow::Cow,
+  collections::{HashMap, HashSet},
+  path::PathBuf,
+  rc::Rc,
+};
+
+use edit::EditCommand;
 use http::{Request, Response};
 
+pub use badge::AppBadge;
+pub use channel::Channel;
 pub use cookie;
 pub use dpi;
 pub use error::*;
 pub use http;
+pub use plugin::Plugin;
 pub use proxy::{ProxyConfig, ProxyEndpoint};
-pub use web_context::WebContext;
+pub use web_context::{ProcessModel, WebContext, WebContextMetrics};
 
 /// A rectangular region.
 #[derive(Clone, Copy, Debug)]
@@ -394,10 +418,35 @@ pub struct WebViewAttributes<'a> {
   pub custom_protocols:
     HashMap<String, Box<dyn Fn(WebViewId, Request<Vec<u8>>, RequestAsyncResponder)>>,
 
+  /// The [`Self::custom_protocols`] whose pages can register service workers, see
+  /// [`WebViewBuilder::with_secure_custom_protocol`].
+  pub secure_custom_protocols: HashSet<String>,
+
+  /// A default `Content-Security-Policy` for the responses of [`Self::custom_protocols`] that don't
+  /// set one, also injected as a `<meta>` tag into [`Self::html`].
+  pub content_security_policy: Option<String>,
+
   /// The IPC handler to receive the message from Javascript on webview
   /// using `window.ipc.postMessage("insert_message_here")` to host Rust code.
   pub ipc_handler: Option<Box<dyn Fn(Request<String>)>>,
 
+  /// The [`Channel`]s the page can open with `window.wryChannels.open("<name>")`.
+  ///
+  /// Channel messages are sent over the IPC transport but never reach [`Self::ipc_handler`].
+  pub channels: Vec<Channel>,
+
+  /// The [`Plugin`]s that get to handle IPC messages before [`Self::ipc_handler`].
+  ///
+  /// Their scripts and custom protocols are registered by [`WebViewBuilder::with_plugin`].
+  pub plugins: Vec<Rc<dyn Plugin>>,
+
+  /// The origins allowed to use the IPC bridge, or `None` to allow every origin.
+  ///
+  /// `window.ipc` is only defined on pages with one of these origins, and messages
+  /// posted from any other origin never reach [`Self::ipc_handler`]. On Linux and Android, the
+  /// origin of the main frame is checked, see [`WebViewBuilder::with_ipc_origins`].
+  pub ipc_origins: Option<Vec<String>>,
+
   /// A handler closure to process incoming [`DragDropEvent`] of the webview.
   ///
   /// # Blocking OS Default Behavior
@@ -411,12 +460,41 @@ pub struct WebViewAttributes<'a> {
   #[cfg(not(feature = "drag-drop"))]
   drag_drop_handler: Option<Box<dyn Fn(DragDropEvent) -> bool>>,
 
+  /// Whether pressing an element with the `data-wry-drag-region` attribute moves the window,
+  /// and double-clicking it toggles maximize, like a native title bar.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android**: Unsupported.
+  pub drag_regions: bool,
+
   /// A navigation handler to decide if incoming url is allowed to navigate.
   ///
   /// The closure take a `String` parameter as url and returns a `bool` to determine whether the navigation should happen.
   /// `true` allows to navigate and `false` does not.
   pub navigation_handler: Option<Box<dyn Fn(String) -> bool>>,
 
+  /// URL patterns that navigations and new windows must match, see
+  /// [`WebViewBuilder::with_navigation_allowlist`] for the syntax. URLs matching none of them
+  /// are blocked before [`Self::navigation_handler`] and [`Self::new_window_req_handler`] are called.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: Custom protocols are loaded from `http://<scheme>.<host>`,
+  ///   or `https://<scheme>.<host>` when using `with_https_scheme`.
+  pub navigation_allowlist: Option<Vec<String>>,
+
+  /// A handler for navigations and new windows to URLs with a scheme the webview can't load,
+  /// like `mailto:`, `tel:` or `magnet:`. Such URLs are never loaded by the webview.
+  ///
+  /// The closure takes the url and returns `true` to open it with the OS default handler,
+  /// or `false` when the app handled it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Opening with the OS default handler is unsupported.
+  pub external_scheme_handler: Option<Box<dyn Fn(String) -> bool>>,
+
   /// A download started handler to manage incoming downloads.
   ///
   /// The closure takes two parameters, the first is a `String` representing the url being downloaded from and and the
@@ -440,6 +518,22 @@ pub struct WebViewAttributes<'a> {
   /// due to API limitations.
   pub download_completed_handler: Option<Rc<dyn Fn(String, Option<PathBuf>, bool) + 'static>>,
 
+  /// A download progress handler, to reflect the downloads on the taskbar or dock.
+  ///
+  /// The closure takes the URL of the original download request, the number of bytes received so far
+  /// and the total size of the download, if known.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS / Android**: Unsupported.
+  pub download_progress_handler: Option<Rc<dyn Fn(String, u64, Option<u64>) + 'static>>,
+
+  /// A handler for the application badge set by the page with `navigator.setAppBadge()`
+  /// and `navigator.clearAppBadge()`, to reflect it on the taskbar or dock.
+  ///
+  /// The badge messages are sent over the IPC transport but never reach [`Self::ipc_handler`].
+  pub badge_handler: Option<Box<dyn Fn(AppBadge)>>,
+
   /// A new window handler to decide if incoming url is allowed to open in a new window.
   ///
   /// The closure take a `String` parameter as url and return `bool` to determine whether the window should open.
@@ -452,6 +546,23 @@ pub struct WebViewAttributes<'a> {
   /// item accelerators to use the clipboard shortcuts.
   pub clipboard: bool,
 
+  /// A handler deciding whether the page at the given url may read the clipboard.
+  /// `true` allows it and `false` denies it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / macOS / Android / iOS**: Unsupported, clipboard access is only controlled by [`Self::clipboard`].
+  pub clipboard_permission_handler: Option<Box<dyn Fn(String) -> bool>>,
+
+  /// A handler deciding whether the page at the given url may use the camera or microphone.
+  /// `true` allows it and `false` denies it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Only the origin of the page is given.
+  /// - **Android**: Unsupported.
+  pub media_capture_permission_handler: Option<Box<dyn Fn(String, MediaCaptureKind) -> bool>>,
+
   /// Enable web inspector which is usually called browser devtools.
   ///
   /// Note this only enables devtools to the webview. To open it, you can call
@@ -479,7 +590,7 @@ pub struct WebViewAttributes<'a> {
   /// - Windows: Setting to `false` does nothing on WebView2 Runtime version before 92.0.902.0,
   /// see https://learn.microsoft.com/en-us/microsoft-edge/webview2/release-notes/archive?tabs=dotnetcsharp#10902-prerelease
   ///
-  /// - **Android / iOS:** Unsupported.
+  /// - **Android:** Unsupported.
   pub back_forward_navigation_gestures: bool,
 
   /// Set a handler closure to process the change of the webview's document title.
@@ -495,12 +606,43 @@ pub struct WebViewAttributes<'a> {
   /// - **Android:** Unsupported yet.
   pub incognito: bool,
 
+  /// Whether the page is allowed to run JavaScript. Initialization scripts and
+  /// [`WebView::evaluate_script`] keep working when it is not.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS**: Requires macOS 11 or iOS 14.
+  /// - **Android:** Unsupported.
+  pub javascript_enabled: bool,
+
   /// Whether all media can be played without user interaction.
   pub autoplay: bool,
 
+  /// The minimum font size in pixels, text of the page is never rendered smaller than this.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Unsupported.
+  pub minimum_font_size: Option<u32>,
+
   /// Set a handler closure to process page load events.
   pub on_page_load_handler: Option<Box<dyn Fn(PageLoadEvent, String)>>,
 
+  /// A handler closure called when a main frame navigation fails.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported.
+  pub navigation_failed_handler: Option<Box<dyn Fn(NavigationError)>>,
+
+  /// A handler closure returning the HTML to show instead of the engine error page
+  /// when a main frame navigation fails.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported.
+  pub error_page_handler: Option<Box<dyn Fn(&NavigationError) -> Option<String>>>,
+
   /// Set a proxy configuration for the webview. Supports HTTP CONNECT and SOCKSv5 proxies
   ///
   /// - **macOS**: Requires macOS 14.0+ and the `mac-proxy` feature flag to be enabled.
@@ -518,6 +660,47 @@ pub struct WebViewAttributes<'a> {
   /// This is only effective if the webview was created by [`WebView::new_as_child`] or [`WebViewBuilder::new_as_child`]
   /// or on Linux, if was created by [`WebViewExtUnix::new_gtk`] or [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
   pub bounds: Option<Rect>,
+
+  /// A handler that receives key events before they are dispatched to the page.
+  /// Returning `true` swallows the event.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: Only receives accelerator keys, that is keys pressed while Ctrl or Alt is held and keys
+  ///   that don't produce a character, such as function keys.
+  /// - **Android / iOS:** Unsupported.
+  pub key_event_handler: Option<Box<dyn Fn(KeyEvent) -> bool>>,
+
+  /// A handler called with `true` when the webview gains keyboard focus and `false` when it loses it.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android / iOS:** Unsupported.
+  pub focus_changed_handler: Option<Box<dyn Fn(bool)>>,
+
+  /// Whether the page is allowed to make an element fullscreen with `element.requestFullscreen()`.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS**: Requires the `fullscreen` feature flag.
+  /// - **Android:** Unsupported.
+  pub fullscreen_content_allowed: bool,
+
+  /// A handler called with `true` when an element of the page enters fullscreen and `false`
+  /// when it leaves it, so the host can make the window fullscreen accordingly.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS**: Requires macOS 13 or iOS 16.
+  /// - **Android:** Unsupported.
+  pub fullscreen_changed_handler: Option<Box<dyn Fn(bool)>>,
+
+  /// A handler called with the favicon of the page when it changes, or `None` when the page has none.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / Android / iOS:** Unsupported.
+  pub favicon_changed_handler: Option<Box<dyn Fn(Option<Icon>)>>,
 }
 
 impl<'a> Default for WebViewAttributes<'a> {
@@ -532,15 +715,27 @@ impl<'a> Default for WebViewAttributes<'a> {
       url: None,
       headers: None,
       html: None,
+      content_security_policy: None,
       initialization_scripts: Default::default(),
       custom_protocols: Default::default(),
+      secure_custom_protocols: Default::default(),
       ipc_handler: None,
+      channels: Vec::new(),
+      plugins: Vec::new(),
+      ipc_origins: None,
       drag_drop_handler: None,
       navigation_handler: None,
+      drag_regions: false,
+      navigation_allowlist: None,
+      external_scheme_handler: None,
       download_started_handler: None,
       download_completed_handler: None,
+      download_progress_handler: None,
+      badge_handler: None,
       new_window_req_handler: None,
       clipboard: false,
+      clipboard_permission_handler: None,
+      media_capture_permission_handler: None,
       #[cfg(debug_assertions)]
       devtools: true,
       #[cfg(not(debug_assertions))]
@@ -550,14 +745,23 @@ impl<'a> Default for WebViewAttributes<'a> {
       back_forward_navigation_gestures: false,
       document_title_changed_handler: None,
       incognito: false,
+      javascript_enabled: true,
       autoplay: true,
+      minimum_font_size: None,
       on_page_load_handler: None,
+      navigation_failed_handler: None,
+      error_page_handler: None,
       proxy_config: None,
       focused: true,
       bounds: Some(Rect {
         position: dpi::LogicalPosition::new(0, 0).into(),
         size: dpi::LogicalSize::new(200, 200).into(),
       }),
+      key_event_handler: None,
+      focus_changed_handler: None,
+      fullscreen_content_allowed: true,
+      fullscreen_changed_handler: None,
+      favicon_changed_handler: None,
     }
   }
 }
@@ -567,6 +771,188 @@ struct WebviewBuilderParts<'a> {
   platform_specific: PlatformSpecificWebViewAttributes,
 }
 
+impl WebviewBuilderParts<'_> {
+  /// Let the plugins see the IPC messages before the IPC handler.
+  fn with_plugins(mut self) -> Self {
+    if !self.attrs.plugins.is_empty() {
+      self.attrs.ipc_handler = Some(plugin::wrap_ipc_handler(
+        std::mem::take(&mut self.attrs.plugins),
+        self.attrs.ipc_handler.take(),
+      ));
+    }
+    self
+  }
+
+  /// Route channel messages out of the IPC handler and inject the channels script.
+  fn with_channels(mut self) -> Self {
+    if !self.attrs.channels.is_empty() {
+      let channels = std::mem::take(&mut self.attrs.channels);
+      self
+        .attrs
+        .initialization_scripts
+        .push(channel::init_script(&channels));
+      self.attrs.ipc_handler = Some(channel::wrap_ipc_handler(
+        channels,
+        self.attrs.ipc_handler.take(),
+      ));
+    }
+    self
+  }
+
+  /// Route the badge messages out of the IPC handler and inject the badge script.
+  fn with_badge(mut self) -> Self {
+    if let Some(badge_handler) = self.attrs.badge_handler.take() {
+      self
+        .attrs
+        .initialization_scripts
+        .push(badge::BADGE_SCRIPT.to_string());
+      self.attrs.ipc_handler = Some(badge::wrap_ipc_handler(
+        badge_handler,
+        self.attrs.ipc_handler.take(),
+      ));
+    }
+    self
+  }
+
+  /// Route the drag region messages out of the IPC handler and inject the drag region scripts,
+  /// the backends move the window.
+  #[cfg(not(any(target_os = "android", target_os = "ios")))]
+  fn with_drag_regions(mut self) -> Self {
+    if self.attrs.drag_regions {
+      self
+        .attrs
+        .initialization_scripts
+        .push(drag_region::DRAG_REGION_SCRIPT.to_string());
+      #[cfg(windows)]
+      self
+        .attrs
+        .initialization_scripts
+        .push(drag_region::DRAG_REGION_STYLE_SCRIPT.to_string());
+      self.attrs.ipc_handler = Some(drag_region::wrap_ipc_handler(
+        self.platform_specific.drag_region_target.clone(),
+        self.attrs.ipc_handler.take(),
+      ));
+    }
+    self
+  }
+
+  /// There is no window to move on mobile.
+  #[cfg(any(target_os = "android", target_os = "ios"))]
+  fn with_drag_regions(self) -> Self {
+    self
+  }
+
+  /// Drop the IPC messages posted from origins that are not allowed.
+  fn with_ipc_origins(mut self) -> Self {
+    if let Some(origins) = &self.attrs.ipc_origins {
+      self.attrs.ipc_handler = self
+        .attrs
+        .ipc_handler
+        .take()
+        .map(|h| ipc::wrap_ipc_handler(origins.clone(), h));
+    }
+    self
+  }
+
+  /// Block the navigations and new windows that are not in the allowlist.
+  fn with_navigation_allowlist(mut self) -> Self {
+    if let Some(allowlist) = self.attrs.navigation_allowlist.take() {
+      self.attrs.navigation_handler = Some(navigation::wrap_handler(
+        allowlist.clone(),
+        self.attrs.navigation_handler.take(),
+      ));
+      self.attrs.new_window_req_handler = Some(navigation::wrap_handler(
+        allowlist,
+        self.attrs.new_window_req_handler.take(),
+      ));
+    }
+    self
+  }
+
+  /// Hand the navigations and new windows to non-web schemes to the external scheme handler.
+  fn with_external_scheme_handler(mut self) -> Self {
+    if let Some(external) = self.attrs.external_scheme_handler.take() {
+      let external: Rc<dyn Fn(String) -> bool> = Rc::from(external);
+      let custom_protocols: Vec<String> = self.attrs.custom_protocols.keys().cloned().collect();
+      self.attrs.navigation_handler = Some(navigation::wrap_external_scheme_handler(
+        custom_protocols.clone(),
+        external.clone(),
+        self.attrs.navigation_handler.take(),
+      ));
+      self.attrs.new_window_req_handler = Some(navigation::wrap_external_scheme_handler(
+        custom_protocols,
+        external,
+        self.attrs.new_window_req_handler.take(),
+      ));
+    }
+    self
+  }
+
+  /// Attach the default Content Security Policy to the custom protocol responses and the html.
+  fn with_content_security_policy(mut self) -> Self {
+    if let Some(csp) = &self.attrs.content_security_policy {
+      if let Ok(value) = http::HeaderValue::from_str(csp) {
+        self.attrs.custom_protocols = std::mem::take(&mut self.attrs.custom_protocols)
+          .into_iter()
+          .map(|(name, handler)| (name, csp::wrap_protocol(value.clone(), handler)))
+          .collect();
+      }
+      self.attrs.html = self
+        .attrs
+        .html
+        .take()
+        .map(|html| csp::inject_meta(csp, &html));
+    }
+    self
+  }
+
+  /// Record custom protocol and IPC activity into the [`WebContext`] metrics, if a context was provided.
+  fn with_context_metrics(mut self) -> Self {
+    if let Some(metrics) = self.attrs.context.as_ref().map(|c| c.metrics.clone()) {
+      self.attrs.custom_protocols = std::mem::take(&mut self.attrs.custom_protocols)
+        .into_iter()
+        .map(|(name, handler)| (name, metrics.wrap_protocol(handler)))
+        .collect();
+      self.attrs.ipc_handler = self.attrs.ipc_handler.take().map(|h| metrics.wrap_ipc(h));
+    }
+    self
+  }
+}
+
+/// The custom protocol that serves [`WebViewBuilder::with_app_shell`].
+const APP_SHELL_PROTOCOL: &str = "wry-app";
+
+/// Schemes that are handled by the webview itself and can't be registered as custom protocols.
+const RESERVED_SCHEMES: &[&str] = &[
+  "about",
+  "blob",
+  "data",
+  "file",
+  "ftp",
+  "http",
+  "https",
+  "javascript",
+  "ws",
+  "wss",
+];
+
+/// Check that `name` is a valid URL scheme, as in RFC 3986, that can be used for a custom protocol.
+fn validate_custom_protocol_name(name: &str) -> Result<()> {
+  let mut chars = name.chars();
+  let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+    && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+  if !valid {
+    return Err(Error::InvalidCustomProtocol(name.to_string()));
+  }
+  if RESERVED_SCHEMES.contains(&name.to_ascii_lowercase().as_str()) {
+    return Err(Error::ReservedCustomProtocol(name.to_string()));
+  }
+  Ok(())
+}
+
+/// Only allows inline styles and `data:` images and fonts, see [`WebViewBuilder::new_sandboxed`].
+const SANDBOXED_CONTENT_SECURITY_POLICY: &str = "default-src 'none'; img-src data:; font-src data:; style-src 'unsafe-inline'; form-action 'none'; base-uri 'none'";
+
 /// Builder type of [`WebView`].
 ///
 /// [`WebViewBuilder`] / [`WebView`] are the basic building blocks to construct WebView contents and
@@ -602,6 +988,40 @@ impl<'a> WebViewBuilder<'a> {
     }
   }
 
+  /// Create a new [`WebViewBuilder`] that renders untrusted `html`, like the body of an email,
+  /// in a locked-down webview.
+  ///
+  /// The page can't run JavaScript, load anything from the network, store data, or navigate.
+  /// Clicked links are passed to `link_handler` instead of being opened.
+  ///
+  /// Navigations the page starts by itself, like a `<meta http-equiv="refresh">`, are passed to
+  /// `link_handler` too and can't be told apart from clicks, so don't open a URL without
+  /// the user's consent. [`WebView::load_url`] and [`WebView::load_html`] are blocked as well,
+  /// create another webview to show other content.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android:** JavaScript can't be turned off, but the navigations it starts are blocked too.
+  pub fn new_sandboxed(html: impl Into<String>, link_handler: impl Fn(String) + 'static) -> Self {
+    let link_handler = Rc::new(link_handler);
+    let new_window_link_handler = link_handler.clone();
+
+    Self::new()
+      .with_html(html)
+      .with_javascript_enabled(false)
+      .with_incognito(true)
+      .with_content_security_policy(SANDBOXED_CONTENT_SECURITY_POLICY)
+      // the html itself doesn't go through the navigation handler, so every navigation is the page's
+      .with_navigation_handler(move |url| {
+        link_handler(url);
+        false
+      })
+      .with_new_window_req_handler(move |url| {
+        new_window_link_handler(url);
+        false
+      })
+  }
+
   /// Create a new [`WebViewBuilder`] with the given [`WebViewAttributes`]
   pub fn with_attributes(attrs: WebViewAttributes<'a>) -> Self {
     Self {
@@ -634,7 +1054,7 @@ impl<'a> WebViewBuilder<'a> {
   ///
   /// ## Platform-specific:
   ///
-  /// - **Android / iOS:** Unsupported.
+  /// - **Android:** Unsupported.
   pub fn with_back_forward_navigation_gestures(self, gesture: bool) -> Self {
     self.and_then(|mut b| {
       b.attrs.back_forward_navigation_gestures = gesture;
@@ -715,6 +1135,17 @@ impl<'a> WebViewBuilder<'a> {
   ///
   /// When registering a custom protocol with the same name, only the last regisered one will be used.
   ///
+  /// The name must be a valid URL scheme and not one the webview handles itself, like `http` or
+  /// `file`, otherwise [`WebViewBuilder::build`] fails with [`Error::InvalidCustomProtocol`] or
+  /// [`Error::ReservedCustomProtocol`].
+  ///
+  /// The response body is a [`Cow<'static, [u8]>`](Cow), returning [`Cow::Borrowed`] for static data
+  /// like `include_bytes!` assets lets the webview read it in place instead of copying it per request.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows / Android:** The body is always copied into the platform stream.
+  ///
   /// # Warning
   ///
   /// Pages loaded from custom protocol will have different Origin on different platforms. And
@@ -739,6 +1170,8 @@ impl<'a> WebViewBuilder<'a> {
     F: Fn(WebViewId, Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> + 'static,
   {
     self.and_then(|mut b| {
+      validate_custom_protocol_name(&name)?;
+
       #[cfg(any(
         target_os = "linux",
         target_os = "dragonfly",
@@ -770,6 +1203,10 @@ impl<'a> WebViewBuilder<'a> {
   ///
   /// When registering a custom protocol with the same name, only the last regisered one will be used.
   ///
+  /// The name must be a valid URL scheme and not one the webview handles itself, like `http` or
+  /// `file`, otherwise [`WebViewBuilder::build`] fails with [`Error::InvalidCustomProtocol`] or
+  /// [`Error::ReservedCustomProtocol`].
+  ///
   /// # Examples
   ///
   /// ```no_run
@@ -791,6 +1228,8 @@ impl<'a> WebViewBuilder<'a> {
     F: Fn(WebViewId, Request<Vec<u8>>, RequestAsyncResponder) + 'static,
   {
     self.and_then(|mut b| {
+      validate_custom_protocol_name(&name)?;
+
       #[cfg(any(
         target_os = "linux",
         target_os = "dragonfly",
@@ -812,6 +1251,41 @@ impl<'a> WebViewBuilder<'a> {
     })
   }
 
+  /// Make the custom protocol `name` a secure origin whose pages can register service workers
+  /// and use `CacheStorage`, so offline-first apps can be served from it. The requests of the
+  /// service workers go to the protocol handler like the ones of the page.
+  ///
+  /// The protocol itself is registered with [`WebViewBuilder::with_custom_protocol`] or
+  /// [`WebViewBuilder::with_asynchronous_custom_protocol`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The protocol must be served over `https`, see [`WebViewBuilderExtWindows::with_https_scheme`].
+  ///   Service worker requests reach the handler only with a WebView2 Runtime that supports `ICoreWebView2_22`.
+  /// - **Linux**: Custom protocols are always secure origins, so `CacheStorage` works, but
+  ///   WebKitGTK only runs service workers for `http(s)` pages.
+  /// - **macOS / iOS / Android**: Unsupported.
+  pub fn with_secure_custom_protocol(self, name: String) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.secure_custom_protocols.insert(name);
+      Ok(b)
+    })
+  }
+
+  /// Set a default `Content-Security-Policy`, e.g. `default-src 'self'`, that is attached to every
+  /// custom protocol response which doesn't already have that header, and injected as a `<meta>`
+  /// tag into the page set with [`WebViewBuilder::with_html`].
+  ///
+  /// Pages loaded with [`WebView::load_html`] or from other schemes are not affected.
+  pub fn with_content_security_policy(self, csp: impl Into<String>) -> Self {
+    self.and_then(|mut b| {
+      let csp = csp.into();
+      http::HeaderValue::from_str(&csp).map_err(http::Error::from)?;
+      b.attrs.content_security_policy = Some(csp);
+      Ok(b)
+    })
+  }
+
   /// Set the IPC handler to receive the message from Javascript on webview
   /// using `window.ipc.postMessage("insert_message_here")` to host Rust code.
   ///
@@ -828,6 +1302,60 @@ impl<'a> WebViewBuilder<'a> {
     })
   }
 
+  /// Only expose the IPC bridge to pages with one of the given origins, e.g. `https://tauri.app`
+  /// or the origin of a custom protocol.
+  ///
+  /// Other pages get no `window.ipc` at all, and messages they manage to post anyway are dropped
+  /// before they reach the IPC handler, [`Channel`]s and [`Plugin`]s.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: The origin of a custom protocol is `http://<scheme>.<host>`,
+  ///   or `https://<scheme>.<host>` when using `with_https_scheme`.
+  /// - **Android**: `window.ipc` is still defined on every page, but messages from other origins are dropped.
+  /// - **Linux / Android**: The engines don't tell which frame posted a message, so it's checked
+  ///   against the origin of the main frame. A cross-origin iframe in an allowed page can still
+  ///   post messages through the engine's message handler, don't embed untrusted frames there.
+  pub fn with_ipc_origins<I, S>(self, origins: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.and_then(|mut b| {
+      b.attrs.ipc_origins = Some(origins.into_iter().map(Into::into).collect());
+      Ok(b)
+    })
+  }
+
+  /// Register a [`Channel`] that the page can open with `window.wryChannels.open("<name>")`
+  /// to stream messages to and from Rust independently of the IPC handler.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: The channel is set up with an initialization script, see [`Self::with_initialization_script`].
+  pub fn with_channel(self, channel: &Channel) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.channels.push(channel.clone());
+      Ok(b)
+    })
+  }
+
+  /// Add a [`Plugin`], registering its initialization scripts and custom protocols
+  /// and letting it handle IPC messages before the IPC handler.
+  pub fn with_plugin<P: Plugin + 'static>(self, plugin: P) -> Self {
+    let mut builder = self;
+    for script in plugin.initialization_scripts() {
+      builder = builder.with_initialization_script(&script);
+    }
+    for (name, handler) in plugin.custom_protocols() {
+      builder = builder.with_asynchronous_custom_protocol(name, handler);
+    }
+    builder.and_then(|mut b| {
+      b.attrs.plugins.push(Rc::new(plugin));
+      Ok(b)
+    })
+  }
+
   /// Set a handler closure to process incoming [`DragDropEvent`] of the webview.
   ///
   /// # Blocking OS Default Behavior
@@ -900,6 +1428,40 @@ impl<'a> WebViewBuilder<'a> {
     })
   }
 
+  /// Load `html` as a self-contained app shell from the `wry-app://localhost/` secure origin,
+  /// without setting up a custom protocol.
+  ///
+  /// Unlike [`WebViewBuilder::with_html`], the page is a secure context with a real origin, so
+  /// it can use `localStorage`, `crypto.subtle` and the other APIs that need one. Its CSS and
+  /// JavaScript have to be inlined, any other path of the origin responds with a `404`.
+  /// To reveal the webview only once the shell is loaded, build it with
+  /// [`WebViewBuilder::with_visible`] set to `false` and show it from the page load handler.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Linux**: The `wry-app` protocol is registered on the [`WebContext`], so only one webview
+  ///   of a context can have an app shell. Building another one with the same context replaces
+  ///   the page served to the first, use a separate [`WebContext`] for each app shell.
+  /// - **Windows / Android**: The origin is `http://wry-app.localhost`,
+  ///   or `https://wry-app.localhost` when using `with_https_scheme`.
+  pub fn with_app_shell(self, html: impl Into<String>) -> Self {
+    let html = html.into().into_bytes();
+    self
+      .with_custom_protocol(APP_SHELL_PROTOCOL.to_string(), move |_id, request| {
+        if request.uri().path() == "/" {
+          Response::builder()
+            .header(http::header::CONTENT_TYPE, "text/html")
+            .body(Cow::Owned(html.clone()))
+        } else {
+          Response::builder()
+            .status(http::StatusCode::NOT_FOUND)
+            .body(Cow::Borrowed(&[][..]))
+        }
+        .unwrap()
+      })
+      .with_url(format!("{APP_SHELL_PROTOCOL}://localhost/"))
+  }
+
   /// Set a custom [user-agent](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/User-Agent) for the WebView.
   ///
   /// ## Platform-specific
@@ -946,10 +1508,38 @@ impl<'a> WebViewBuilder<'a> {
     })
   }
 
+  /// Let elements with the `data-wry-drag-region` attribute move the window when pressed,
+  /// and toggle maximize when double-clicked, like a native title bar. Default is `false`.
+  ///
+  /// Only the marked element itself is a drag region, not its children, so buttons
+  /// placed in a custom title bar keep working.
+  ///
+  /// ```html
+  /// <div data-wry-drag-region class="titlebar"><button>×</button></div>
+  /// ```
+  ///
+  /// The drag region messages are sent over the IPC transport but never reach the IPC handler.
+  /// Like any IPC message, they are dropped on the origins not allowed by [`Self::with_ipc_origins`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The drag regions are also styled with `app-region: drag`, so the runtimes
+  ///   with non-client region support move the window natively and show the system menu on right click.
+  /// - **iOS / Android**: Unsupported.
+  pub fn with_drag_regions(self, enabled: bool) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.drag_regions = enabled;
+      Ok(b)
+    })
+  }
+
   /// Set a navigation handler to decide if incoming url is allowed to navigate.
   ///
   /// The closure take a `String` parameter as url and returns a `bool` to determine whether the navigation should happen.
   /// `true` allows to navigate and `false` does not.
+  ///
+  /// The handler is not called for the HTML given to [`WebViewBuilder::with_html`], nor for the
+  /// error page of [`WebViewBuilder::with_error_page_handler`].
   pub fn with_navigation_handler(self, callback: impl Fn(String) -> bool + 'static) -> Self {
     self.and_then(|mut b| {
       b.attrs.navigation_handler = Some(Box::new(callback));
@@ -957,6 +1547,62 @@ impl<'a> WebViewBuilder<'a> {
     })
   }
 
+  /// Only allow navigating to, and opening new windows for, URLs that match one of the given
+  /// patterns, e.g. `https://tauri.app/*`, `https://*.tauri.app/*` or `wry://*`.
+  ///
+  /// The scheme, host, port and path of a pattern are matched separately:
+  /// - The host is matched exactly, `*` matches any host and `*.tauri.app` any subdomain of `tauri.app`.
+  /// - The port is matched exactly, `*` matches any port. Without one, only URLs without a port match.
+  /// - In the path and query, `*` matches any sequence of characters. Without a path, any path matches.
+  ///
+  /// Patterns without `//`, like `about:blank`, match the rest of the URL after the scheme,
+  /// with `*` matching any sequence of characters.
+  ///
+  /// Other navigations are blocked without calling the navigation and new window handlers,
+  /// which makes this a safety net that doesn't depend on how those handlers are written.
+  /// Note that the URL given to [`WebViewBuilder::with_url`] has to be allowed too, while the
+  /// HTML given to [`WebViewBuilder::with_html`] is always loaded.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: Custom protocols are loaded from `http://<scheme>.<host>`,
+  ///   or `https://<scheme>.<host>` when using `with_https_scheme`, so use a pattern like `http://wry.localhost/*`.
+  pub fn with_navigation_allowlist<I, S>(self, patterns: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.and_then(|mut b| {
+      b.attrs.navigation_allowlist = Some(patterns.into_iter().map(Into::into).collect());
+      Ok(b)
+    })
+  }
+
+  /// Set a handler for navigations and new windows to URLs with a scheme the webview can't load,
+  /// like `mailto:`, `tel:` or `magnet:`, instead of dropping them.
+  ///
+  /// The closure takes the url and returns `true` to open it with the OS default handler,
+  /// or `false` when the app handled it. The webview never loads such URLs, and the navigation
+  /// and new window handlers are not called for them.
+  ///
+  /// The page decides which URLs reach this handler, and the OS default handler of a scheme can
+  /// be any application registered for it, so only return `true` for the schemes you expect.
+  ///
+  /// The external scheme handler runs before [`Self::with_navigation_allowlist`], so URLs with
+  /// a scheme the webview can't load are given to it even if they match none of the patterns.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: URLs without a valid scheme at least two characters long, like `C:\file`,
+  ///   are never opened.
+  /// - **Android**: Opening with the OS default handler is unsupported.
+  pub fn with_external_scheme_handler(self, callback: impl Fn(String) -> bool + 'static) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.external_scheme_handler = Some(Box::new(callback));
+      Ok(b)
+    })
+  }
+
   /// Set a download started handler to manage incoming downloads.
   ///
   //// The closure takes two parameters, the first is a `String` representing the url being downloaded from and and the
@@ -996,6 +1642,36 @@ impl<'a> WebViewBuilder<'a> {
     })
   }
 
+  /// Sets a download progress handler, to reflect the downloads on the taskbar or dock.
+  ///
+  /// The closure takes the URL of the original download request, the number of bytes received so far
+  /// and the total size of the download, if known.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS / Android**: Unsupported.
+  pub fn with_download_progress_handler(
+    self,
+    download_progress_handler: impl Fn(String, u64, Option<u64>) + 'static,
+  ) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.download_progress_handler = Some(Rc::new(download_progress_handler));
+      Ok(b)
+    })
+  }
+
+  /// Set a handler for the application badge set by the page with `navigator.setAppBadge()`
+  /// and `navigator.clearAppBadge()`, so it can be shown on the taskbar or dock.
+  ///
+  /// This defines both functions in the page, on top of the engine's own implementation if any.
+  /// The badge messages are sent over the IPC transport but never reach the IPC handler.
+  pub fn with_badge_handler(self, handler: impl Fn(AppBadge) + 'static) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.badge_handler = Some(Box::new(handler));
+      Ok(b)
+    })
+  }
+
   /// Enables clipboard access for the page rendered on **Linux** and **Windows**.
   ///
   /// macOS doesn't provide such method and is always enabled by default. But your app will still need to add menu
@@ -1007,24 +1683,66 @@ impl<'a> WebViewBuilder<'a> {
     })
   }
 
-  /// Set a new window request handler to decide if incoming url is allowed to be opened.
+  /// Set a handler that is asked when the page wants to read the clipboard, for example with
+  /// `navigator.clipboard.readText()`, instead of silently allowing or prompting the user.
   ///
-  /// The closure take a `String` parameter as url and return `bool` to determine whether the window should open.
-  /// `true` allows to open and `false` does not.
-  pub fn with_new_window_req_handler(self, callback: impl Fn(String) -> bool + 'static) -> Self {
+  /// The closure takes the url of the page as a `String` and returns `true` to allow reading
+  /// the clipboard or `false` to deny it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / macOS / Android / iOS**: Unsupported, use [`WebViewBuilder::with_clipboard`] instead.
+  pub fn with_clipboard_permission_handler(
+    self,
+    handler: impl Fn(String) -> bool + 'static,
+  ) -> Self {
     self.and_then(|mut b| {
-      b.attrs.new_window_req_handler = Some(Box::new(callback));
+      b.attrs.clipboard_permission_handler = Some(Box::new(handler));
       Ok(b)
     })
   }
 
-  /// Sets whether clicking an inactive window also clicks through to the webview. Default is `false`.
+  /// Set a handler that is asked when the page wants to use the camera or microphone,
+  /// for example with `navigator.mediaDevices.getUserMedia()`.
+  ///
+  /// The closure takes the url of the page as a `String` and the requested devices, and returns
+  /// `true` to allow using them or `false` to deny it. Without a handler, the requests are
+  /// granted on macOS and iOS, and left to the engine on other platforms.
   ///
   /// ## Platform-specific
   ///
-  /// This configuration only impacts macOS.
-  pub fn with_accept_first_mouse(self, accept_first_mouse: bool) -> Self {
-    self.and_then(|mut b| {
+  /// - **Linux**: Also enables `getUserMedia`, which WebKitGTK turns off by default.
+  /// - **macOS / iOS**: Only the origin of the page is given.
+  /// - **Windows**: Camera and microphone are requested separately.
+  /// - **Android**: Unsupported.
+  pub fn with_media_capture_permission_handler(
+    self,
+    handler: impl Fn(String, MediaCaptureKind) -> bool + 'static,
+  ) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.media_capture_permission_handler = Some(Box::new(handler));
+      Ok(b)
+    })
+  }
+
+  /// Set a new window request handler to decide if incoming url is allowed to be opened.
+  ///
+  /// The closure take a `String` parameter as url and return `bool` to determine whether the window should open.
+  /// `true` allows to open and `false` does not.
+  pub fn with_new_window_req_handler(self, callback: impl Fn(String) -> bool + 'static) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.new_window_req_handler = Some(Box::new(callback));
+      Ok(b)
+    })
+  }
+
+  /// Sets whether clicking an inactive window also clicks through to the webview. Default is `false`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// This configuration only impacts macOS.
+  pub fn with_accept_first_mouse(self, accept_first_mouse: bool) -> Self {
+    self.and_then(|mut b| {
       b.attrs.accept_first_mouse = accept_first_mouse;
       Ok(b)
     })
@@ -1053,6 +1771,34 @@ impl<'a> WebViewBuilder<'a> {
     })
   }
 
+  /// Whether the page is allowed to run JavaScript. Default is `true`.
+  ///
+  /// Initialization scripts and [`WebView::evaluate_script`] keep working when it is not.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS**: Requires macOS 11 or iOS 14.
+  /// - **Android:** Unsupported.
+  pub fn with_javascript_enabled(self, enabled: bool) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.javascript_enabled = enabled;
+      Ok(b)
+    })
+  }
+
+  /// Set the minimum font size in pixels, so that text of the page is never rendered smaller than
+  /// this, e.g. to honor the accessibility text size of the user.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Unsupported.
+  pub fn with_minimum_font_size(self, size: u32) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.minimum_font_size = Some(size);
+      Ok(b)
+    })
+  }
+
   /// Set a handler to process page loading events.
   pub fn with_on_page_load_handler(
     self,
@@ -1064,6 +1810,48 @@ impl<'a> WebViewBuilder<'a> {
     })
   }
 
+  /// Set a handler called when a main frame navigation fails, e.g. to show a custom
+  /// offline page instead of the engine default error page.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 1.0.2420.47 or higher to report
+  ///   [`NavigationErrorKind::HttpStatus`].
+  /// - **Android**: Unsupported.
+  pub fn with_navigation_failed_handler(self, handler: impl Fn(NavigationError) + 'static) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.navigation_failed_handler = Some(Box::new(handler));
+      Ok(b)
+    })
+  }
+
+  /// Set a handler returning the HTML to show instead of the engine error page when a
+  /// main frame navigation fails, or `None` to keep the engine error page.
+  ///
+  /// The handler is not called for [`NavigationErrorKind::HttpStatus`] errors since the
+  /// server response is shown then, nor for [`NavigationErrorKind::Cancelled`] navigations.
+  ///
+  /// The error page is loaded as `about:blank` with an opaque origin, so it can not reach the
+  /// cookies or storage of the failing site, and the navigation handler and
+  /// [`WebViewBuilder::with_navigation_allowlist`] are not asked for it. Reloading shows the
+  /// error page again, load [`NavigationError::url`] to retry instead.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The URL of the webview becomes `about:blank`. The error page can not be
+  ///   larger than 2 MB.
+  /// - **macOS / iOS / Linux**: The URL of the webview becomes `about:blank`.
+  /// - **Android**: Unsupported.
+  pub fn with_error_page_handler(
+    self,
+    handler: impl Fn(&NavigationError) -> Option<String> + 'static,
+  ) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.error_page_handler = Some(Box::new(handler));
+      Ok(b)
+    })
+  }
+
   /// Set a proxy configuration for the webview.
   ///
   /// - **macOS**: Requires macOS 14.0+ and the `mac-proxy` feature flag to be enabled. Supports HTTP CONNECT and SOCKSv5 proxies.
@@ -1099,6 +1887,95 @@ impl<'a> WebViewBuilder<'a> {
     })
   }
 
+  /// Set a handler that receives key events before they are dispatched to the page, so application
+  /// shortcuts keep working while the webview is focused. Return `true` to swallow the event.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: Only receives accelerator keys, that is keys pressed while Ctrl or Alt is held and keys
+  ///   that don't produce a character, such as function keys.
+  /// - **macOS**: Only reported while the webview is focused. Key equivalents, keys pressed while
+  ///   Cmd or Ctrl is held, are only reported when pressed.
+  /// - **Android / iOS:** Unsupported.
+  pub fn with_key_event_handler<F>(self, handler: F) -> Self
+  where
+    F: Fn(KeyEvent) -> bool + 'static,
+  {
+    self.and_then(|mut b| {
+      b.attrs.key_event_handler = Some(Box::new(handler));
+      Ok(b)
+    })
+  }
+
+  /// Set a handler called with `true` when the webview gains keyboard focus and `false` when it loses it,
+  /// for example after [`WebView::focus`] or [`WebView::focus_parent`].
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android / iOS:** Unsupported.
+  pub fn with_focus_changed_handler<F>(self, handler: F) -> Self
+  where
+    F: Fn(bool) + 'static,
+  {
+    self.and_then(|mut b| {
+      b.attrs.focus_changed_handler = Some(Box::new(handler));
+      Ok(b)
+    })
+  }
+
+  /// Whether the page is allowed to make an element, like a video player, fullscreen.
+  /// Default is `true`.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS**: Requires the `fullscreen` feature flag.
+  /// - **Android:** Unsupported.
+  pub fn with_fullscreen_content_allowed(self, allowed: bool) -> Self {
+    self.and_then(|mut b| {
+      b.attrs.fullscreen_content_allowed = allowed;
+      Ok(b)
+    })
+  }
+
+  /// Set a handler called with `true` when an element of the page enters fullscreen and `false`
+  /// when it leaves it.
+  ///
+  /// The webview only fills its own bounds, so use this to make the native window fullscreen
+  /// and resize the webview to cover it.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Linux**: WebKitGTK also makes the toplevel window fullscreen on its own.
+  /// - **macOS / iOS**: Requires macOS 13 or iOS 16.
+  /// - **Android:** Unsupported.
+  pub fn with_fullscreen_changed_handler<F>(self, handler: F) -> Self
+  where
+    F: Fn(bool) + 'static,
+  {
+    self.and_then(|mut b| {
+      b.attrs.fullscreen_changed_handler = Some(Box::new(handler));
+      Ok(b)
+    })
+  }
+
+  /// Set a handler called with the favicon of the page, decoded to RGBA, whenever it changes,
+  /// or with `None` when the page has no favicon.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Linux**: Turns on the favicon database of the [`WebContext`] used by the webview.
+  /// - **Windows**: Requires WebView2 Runtime version 103.0.1264.37 or higher, does nothing on older versions.
+  /// - **macOS / Android / iOS:** Unsupported.
+  pub fn with_favicon_changed_handler<F>(self, handler: F) -> Self
+  where
+    F: Fn(Option<Icon>) + 'static,
+  {
+    self.and_then(|mut b| {
+      b.attrs.favicon_changed_handler = Some(Box::new(handler));
+      Ok(b)
+    })
+  }
+
   /// Consume the builder and create the [`WebView`] from a type that implements [`HasWindowHandle`].
   ///
   /// # Platform-specific:
@@ -1116,7 +1993,17 @@ impl<'a> WebViewBuilder<'a> {
   /// - Panics if the provided handle was not supported or invalid.
   /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
   pub fn build<W: HasWindowHandle>(self, window: &'a W) -> Result<WebView> {
-    let parts = self.inner?;
+    let parts = self
+      .inner?
+      .with_plugins()
+      .with_channels()
+      .with_badge()
+      .with_drag_regions()
+      .with_ipc_origins()
+      .with_navigation_allowlist()
+      .with_external_scheme_handler()
+      .with_content_security_policy()
+      .with_context_metrics();
 
     InnerWebView::new(window, parts.attrs, parts.platform_specific)
       .map(|webview| WebView { webview })
@@ -1145,7 +2032,17 @@ impl<'a> WebViewBuilder<'a> {
   /// - Panics if the provided handle was not support or invalid.
   /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
   pub fn build_as_child<W: HasWindowHandle>(self, window: &'a W) -> Result<WebView> {
-    let parts = self.inner?;
+    let parts = self
+      .inner?
+      .with_plugins()
+      .with_channels()
+      .with_badge()
+      .with_drag_regions()
+      .with_ipc_origins()
+      .with_navigation_allowlist()
+      .with_external_scheme_handler()
+      .with_content_security_policy()
+      .with_context_metrics();
 
     InnerWebView::new_as_child(window, parts.attrs, parts.platform_specific)
       .map(|webview| WebView { webview })
@@ -1156,6 +2053,12 @@ impl<'a> WebViewBuilder<'a> {
 #[derive(Clone, Default)]
 pub(crate) struct PlatformSpecificWebViewAttributes {
   data_store_identifier: Option<[u8; 16]>,
+  #[cfg(target_os = "macos")]
+  edit_key_equivalents: bool,
+  #[cfg(target_os = "macos")]
+  theme: Option<Theme>,
+  #[cfg(target_os = "macos")]
+  drag_region_target: drag_region::DragRegionTarget,
 }
 
 #[cfg(any(target_os = "macos", target_os = "ios",))]
@@ -1177,6 +2080,39 @@ impl WebViewBuilderExtDarwin for WebViewBuilder<'_> {
   }
 }
 
+#[cfg(target_os = "macos")]
+pub trait WebViewBuilderExtMacOS {
+  /// Handle the standard Edit menu shortcuts, <kbd>⌘X</kbd>, <kbd>⌘C</kbd>, <kbd>⌘V</kbd>,
+  /// <kbd>⌘A</kbd>, <kbd>⌘Z</kbd> and <kbd>⇧⌘Z</kbd>, when the webview is focused and
+  /// the app's main menu has no item for them. Default is `false`.
+  ///
+  /// Without this, an app with no Edit menu, or a borderless window with its own menu,
+  /// can't cut, copy, paste or undo in the page with the keyboard.
+  fn with_edit_key_equivalents(self, enabled: bool) -> Self;
+
+  /// Specifies the theme of the webview, which is what `prefers-color-scheme` reports to the page.
+  ///
+  /// Defaults to [`Theme::Auto`] which follows the appearance of the window.
+  fn with_theme(self, theme: Theme) -> Self;
+}
+
+#[cfg(target_os = "macos")]
+impl WebViewBuilderExtMacOS for WebViewBuilder<'_> {
+  fn with_edit_key_equivalents(self, enabled: bool) -> Self {
+    self.and_then(|mut b| {
+      b.platform_specific.edit_key_equivalents = enabled;
+      Ok(b)
+    })
+  }
+
+  fn with_theme(self, theme: Theme) -> Self {
+    self.and_then(|mut b| {
+      b.platform_specific.theme = Some(theme);
+      Ok(b)
+    })
+  }
+}
+
 #[cfg(windows)]
 #[derive(Clone)]
 pub(crate) struct PlatformSpecificWebViewAttributes {
@@ -1186,6 +2122,12 @@ pub(crate) struct PlatformSpecificWebViewAttributes {
   use_https: bool,
   scroll_bar_style: ScrollBarStyle,
   browser_extensions_enabled: bool,
+  language: Option<String>,
+  release_channel_preference: ReleaseChannelPreference,
+  browser_executable_folder: Option<PathBuf>,
+  virtual_host_mappings: Vec<(String, PathBuf, HostResourceAccessKind)>,
+  accelerator_key_handler: Option<Rc<dyn Fn(AcceleratorKeyEvent) -> bool>>,
+  drag_region_target: drag_region::DragRegionTarget,
 }
 
 #[cfg(windows)]
@@ -1198,6 +2140,12 @@ impl Default for PlatformSpecificWebViewAttributes {
       use_https: false, // To match macOS & Linux behavior in the context of mixed content.
       scroll_bar_style: ScrollBarStyle::default(),
       browser_extensions_enabled: false,
+      language: None,
+      release_channel_preference: ReleaseChannelPreference::default(),
+      browser_executable_folder: None,
+      virtual_host_mappings: Vec::new(),
+      accelerator_key_handler: None,
+      drag_region_target: Default::default(),
     }
   }
 }
@@ -1257,6 +2205,56 @@ pub trait WebViewBuilderExtWindows {
   /// Requires WebView2 Runtime version 1.0.2210.55 or higher, does nothing on older versions,
   /// see https://learn.microsoft.com/en-us/microsoft-edge/webview2/release-notes/archive?tabs=dotnetcsharp#10221055
   fn with_browser_extensions_enabled(self, enabled: bool) -> Self;
+
+  /// Sets the default display language of the webview, for example `en-US` or `fr`.
+  /// This affects the browser UI and the `Accept-Language` header.
+  ///
+  /// Defaults to the user's UI language.
+  ///
+  /// ## Warning
+  ///
+  /// Webview instances with different languages must also have different [data directories](struct.WebContext.html#method.new).
+  fn with_language<S: Into<String>>(self, language: S) -> Self;
+
+  /// Sets which installed WebView2 runtime is preferred when creating the webview environment.
+  /// The runtime is installed per machine, to pin the user data folder use [`WebContext::new`] instead.
+  ///
+  /// Defaults to [`ReleaseChannelPreference::MostStable`].
+  ///
+  /// Requires WebView2 Runtime version 1.0.2365.46 or higher, does nothing on older versions,
+  /// see https://learn.microsoft.com/en-us/microsoft-edge/webview2/release-notes/archive?tabs=dotnetcsharp#10236546
+  fn with_release_channel_preference(self, preference: ReleaseChannelPreference) -> Self;
+
+  /// Use a fixed version WebView2 runtime located in `path` instead of the installed evergreen runtime.
+  ///
+  /// The folder must contain `msedgewebview2.exe`, see <https://learn.microsoft.com/en-us/microsoft-edge/webview2/concepts/distribution#details-about-the-fixed-version-runtime-distribution-mode>.
+  /// When set, [`Self::with_release_channel_preference`] has no effect.
+  fn with_browser_executable_folder<P: Into<PathBuf>>(self, path: P) -> Self;
+
+  /// Serve the files of `folder` from `https://<host>` and `http://<host>`, giving them a real
+  /// origin so secure-context APIs are available, unlike custom protocols served from `http://<scheme>.*`.
+  ///
+  /// The mapping is set before the initial url is loaded, so it can be used with [`WebViewBuilder::with_url`].
+  /// Use a reserved domain like `app.localhost` or `app.example` to avoid clashing with real websites.
+  ///
+  /// Requires WebView2 Runtime version 86.0.622.38 or higher, see <https://learn.microsoft.com/en-us/microsoft-edge/webview2/reference/win32/icorewebview2_3#setvirtualhostnametofoldermapping>
+  fn with_virtual_host_name_to_folder_mapping<H: Into<String>, P: Into<PathBuf>>(
+    self,
+    host: H,
+    folder: P,
+    access: HostResourceAccessKind,
+  ) -> Self;
+
+  /// Set a handler that receives accelerator keys, such as `Ctrl+F`, `Ctrl+P` or `F5`,
+  /// before WebView2 handles them. Return `true` to mark the key as handled, which stops
+  /// WebView2 from running its default action and from dispatching the key to the page.
+  ///
+  /// This can be used to give the application's own shortcuts precedence over the browser ones,
+  /// while [`Self::with_browser_accelerator_keys`] disables the browser ones altogether.
+  fn with_accelerator_key_handler<F: Fn(AcceleratorKeyEvent) -> bool + 'static>(
+    self,
+    handler: F,
+  ) -> Self;
 }
 
 #[cfg(windows)]
@@ -1302,6 +2300,51 @@ impl WebViewBuilderExtWindows for WebViewBuilder<'_> {
       Ok(b)
     })
   }
+
+  fn with_language<S: Into<String>>(self, language: S) -> Self {
+    self.and_then(|mut b| {
+      b.platform_specific.language = Some(language.into());
+      Ok(b)
+    })
+  }
+
+  fn with_release_channel_preference(self, preference: ReleaseChannelPreference) -> Self {
+    self.and_then(|mut b| {
+      b.platform_specific.release_channel_preference = preference;
+      Ok(b)
+    })
+  }
+
+  fn with_browser_executable_folder<P: Into<PathBuf>>(self, path: P) -> Self {
+    self.and_then(|mut b| {
+      b.platform_specific.browser_executable_folder = Some(path.into());
+      Ok(b)
+    })
+  }
+
+  fn with_virtual_host_name_to_folder_mapping<H: Into<String>, P: Into<PathBuf>>(
+    self,
+    host: H,
+    folder: P,
+    access: HostResourceAccessKind,
+  ) -> Self {
+    self.and_then(|mut b| {
+      b.platform_specific
+        .virtual_host_mappings
+        .push((host.into(), folder.into(), access));
+      Ok(b)
+    })
+  }
+
+  fn with_accelerator_key_handler<F: Fn(AcceleratorKeyEvent) -> bool + 'static>(
+    self,
+    handler: F,
+  ) -> Self {
+    self.and_then(|mut b| {
+      b.platform_specific.accelerator_key_handler = Some(Rc::new(handler));
+      Ok(b)
+    })
+  }
 }
 
 #[cfg(target_os = "android")]
@@ -1391,7 +2434,8 @@ impl WebViewBuilderExtAndroid for WebViewBuilder<'_> {
 pub trait WebViewBuilderExtUnix<'a> {
   /// Consume the builder and create the webview inside a GTK container widget, such as GTK window.
   ///
-  /// - If the container is [`gtk::Box`], it is added using [`Box::pack_start(webview, true, true, 0)`](gtk::prelude::BoxExt::pack_start).
+  /// - If the container is [`gtk::Box`], it is added using [`Box::pack_start(webview, true, true, 0)`](gtk::prelude::BoxExt::pack_start),
+  ///   see [`Self::with_gtk_box_packing`] to change how it shares the box with other widgets.
   /// - If the container is [`gtk::Fixed`], its [size request](gtk::prelude::WidgetExt::set_size_request) will be set using the (width, height) bounds passed in
   ///   and will be added to the container using [`Fixed::put`](gtk::prelude::FixedExt::put) using the (x, y) bounds passed in.
   /// - For all other containers, it will be added using [`gtk::prelude::ContainerExt::add`]
@@ -1402,6 +2446,26 @@ pub trait WebViewBuilderExtUnix<'a> {
   fn build_gtk<W>(self, widget: &'a W) -> Result<WebView>
   where
     W: gtk::prelude::IsA<gtk::Container>;
+
+  /// Set the `expand`, `fill` and `padding` arguments used to pack the webview when
+  /// [`Self::build_gtk`] is called with a [`gtk::Box`], so native widgets such as sidebars
+  /// or status bars can be packed in the same box.
+  ///
+  /// The webview is packed after the widgets already in the box, use
+  /// [`BoxExt::reorder_child`](gtk::prelude::BoxExt::reorder_child) with
+  /// [`WebViewExtUnix::webview`] to move it elsewhere.
+  ///
+  /// Defaults to `expand: true`, `fill: true` and `padding: 0`.
+  fn with_gtk_box_packing(self, expand: bool, fill: bool, padding: u32) -> Self;
+
+  /// Set a handler for the messages the web extensions send to this webview with
+  /// `webkit_web_page_send_message_to_view`, see [`WebContextExtUnix::set_web_extensions_directory`].
+  ///
+  /// Return `true` if the message was handled, the handler can reply with
+  /// [`UserMessageExt::send_reply`](webkit2gtk::UserMessageExt::send_reply).
+  fn with_web_extension_message_handler<F>(self, handler: F) -> Self
+  where
+    F: Fn(&webkit2gtk::UserMessage) -> bool + 'static;
 }
 
 #[cfg(any(
@@ -1416,11 +2480,40 @@ impl<'a> WebViewBuilderExtUnix<'a> for WebViewBuilder<'a> {
   where
     W: gtk::prelude::IsA<gtk::Container>,
   {
-    let parts = self.inner?;
+    let parts = self
+      .inner?
+      .with_plugins()
+      .with_channels()
+      .with_badge()
+      .with_drag_regions()
+      .with_ipc_origins()
+      .with_navigation_allowlist()
+      .with_external_scheme_handler()
+      .with_content_security_policy()
+      .with_context_metrics();
 
     InnerWebView::new_gtk(widget, parts.attrs, parts.platform_specific)
       .map(|webview| WebView { webview })
   }
+
+  fn with_gtk_box_packing(self, expand: bool, fill: bool, padding: u32) -> Self {
+    self.and_then(|mut b| {
+      b.platform_specific.box_expand = expand;
+      b.platform_specific.box_fill = fill;
+      b.platform_specific.box_padding = padding;
+      Ok(b)
+    })
+  }
+
+  fn with_web_extension_message_handler<F>(self, handler: F) -> Self
+  where
+    F: Fn(&webkit2gtk::UserMessage) -> bool + 'static,
+  {
+    self.and_then(|mut b| {
+      b.platform_specific.web_extension_message_handler = Some(Box::new(handler));
+      Ok(b)
+    })
+  }
 }
 
 /// The fundamental type to present a [`WebView`].
@@ -1428,6 +2521,11 @@ impl<'a> WebViewBuilderExtUnix<'a> for WebViewBuilder<'a> {
 /// [`WebViewBuilder`] / [`WebView`] are the basic building blocks to construct WebView contents and
 /// scripts for those who prefer to control fine grained window creation and event handling.
 /// [`WebView`] presents the actual WebView window and let you still able to perform actions on it.
+///
+/// # Destruction
+///
+/// The native webview is torn down when the [`WebView`] is dropped or [closed](WebView::close).
+/// Do it before destroying the window the webview was built in.
 pub struct WebView {
   webview: InnerWebView,
 }
@@ -1513,11 +2611,180 @@ impl WebView {
     self.webview.eval(js, Some(callback))
   }
 
+  /// Get the scroll position of the page in CSS pixels, `callback` is called with it,
+  /// or with [`Error::InvalidScrollPosition`] if the page didn't report a position.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS**: Read from the content offset of the scroll view, `callback` is called right away.
+  /// - **Windows / macOS / Linux**: Read with JavaScript, so `callback` is never called when
+  ///   JavaScript is disabled.
+  /// - **Android**: Unsupported, see [`Self::evaluate_script_with_callback`].
+  pub fn scroll_position<F>(&self, callback: F) -> Result<()>
+  where
+    F: Fn(Result<dpi::LogicalPosition<f64>>) + Send + 'static,
+  {
+    #[cfg(target_os = "ios")]
+    {
+      callback(Ok(self.webview.scroll_position()));
+      Ok(())
+    }
+    #[cfg(not(target_os = "ios"))]
+    {
+      self.evaluate_script_with_callback("[window.scrollX, window.scrollY]", move |result| {
+        let mut coords = result
+          .trim_matches(|c| c == '[' || c == ']')
+          .split(',')
+          .map(|c| c.trim().parse::<f64>());
+        callback(match (coords.next(), coords.next(), coords.next()) {
+          (Some(Ok(x)), Some(Ok(y)), None) => Ok(dpi::LogicalPosition::new(x, y)),
+          _ => Err(Error::InvalidScrollPosition(result)),
+        });
+      })
+    }
+  }
+
+  /// Scroll the page to the given position in CSS pixels, with a smooth animation or instantly.
+  ///
+  /// Returns [`Error::NonFiniteScrollPosition`] if a coordinate is `NaN` or infinite.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS**: Sets the content offset of the scroll view.
+  /// - **Windows / macOS / Linux / Android**: Scrolls with JavaScript, so nothing happens when
+  ///   JavaScript is disabled.
+  pub fn set_scroll_position(
+    &self,
+    position: dpi::LogicalPosition<f64>,
+    smooth: bool,
+  ) -> Result<()> {
+    if !position.x.is_finite() || !position.y.is_finite() {
+      return Err(Error::NonFiniteScrollPosition(position.x, position.y));
+    }
+
+    #[cfg(target_os = "ios")]
+    {
+      self.webview.set_scroll_position(position, smooth);
+      Ok(())
+    }
+    #[cfg(not(target_os = "ios"))]
+    {
+      let behavior = if smooth { "smooth" } else { "instant" };
+      self.evaluate_script(&format!(
+        "window.scrollTo({{ left: {}, top: {}, behavior: '{behavior}' }})",
+        position.x, position.y
+      ))
+    }
+  }
+
+  /// Save the page to `path` in the given format, `handler` is called once the file is written.
+  ///
+  /// Returns [`Error::UnsupportedSaveFormat`] if the format is not supported on this platform.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 1.0.1185.39 or higher to save as PDF.
+  /// - **macOS**: Requires macOS 11.0+.
+  /// - **iOS**: Requires iOS 14.0+.
+  /// - **Android**: Unsupported.
+  pub fn save_page<F>(&self, path: &std::path::Path, format: SaveFormat, handler: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + 'static,
+  {
+    self.webview.save_page(path, format, handler)
+  }
+
+  /// Get the back/forward history of the webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: Unsupported, always returns an empty [`BackForwardList`].
+  pub fn back_forward_list(&self) -> Result<BackForwardList> {
+    self.webview.back_forward_list()
+  }
+
+  /// Serialize the navigation state of the webview, including its back/forward history,
+  /// so it can be restored with [`WebView::restore_session`], for example after a restart.
+  ///
+  /// The data is opaque and only meant to be restored by the same engine.
+  ///
+  /// Returns [`Error::UnsupportedSessionRestore`] if sessions are not supported on this platform.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Requires macOS 12 or iOS 15.
+  /// - **Windows / Android**: Unsupported.
+  pub fn serialize_session(&self) -> Result<Vec<u8>> {
+    self.webview.serialize_session()
+  }
+
+  /// Restore the navigation state saved with [`WebView::serialize_session`], which navigates
+  /// to the page that was current at the time.
+  ///
+  /// Returns [`Error::UnsupportedSessionRestore`] if sessions are not supported on this platform.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Requires macOS 12 or iOS 15.
+  /// - **Windows / Android**: Unsupported.
+  pub fn restore_session(&self, session: &[u8]) -> Result<()> {
+    self.webview.restore_session(session)
+  }
+
   /// Launch print modal for the webview content.
   pub fn print(&self) -> Result<()> {
     self.webview.print()
   }
 
+  /// Get the text currently selected in the page, which is empty if nothing is selected.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported, the callback is never called.
+  pub fn selected_text(&self, callback: impl Fn(String) + Send + 'static) -> Result<()> {
+    self.webview.eval(
+      edit::SELECTED_TEXT_SCRIPT,
+      Some(move |result: String| callback(json::decode_string(&result).unwrap_or_default())),
+    )
+  }
+
+  /// Deselect everything in the page.
+  pub fn clear_selection(&self) -> Result<()> {
+    self.evaluate_script(edit::CLEAR_SELECTION_SCRIPT)
+  }
+
+  /// Copy the page selection to the clipboard.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires [`WebViewBuilder::with_clipboard`].
+  pub fn copy(&self) -> Result<()> {
+    self.webview.edit(EditCommand::Copy)
+  }
+
+  /// Cut the page selection to the clipboard.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires [`WebViewBuilder::with_clipboard`].
+  pub fn cut(&self) -> Result<()> {
+    self.webview.edit(EditCommand::Cut)
+  }
+
+  /// Paste the clipboard content into the focused element of the page.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: Unsupported, the engine only lets the user paste.
+  pub fn paste(&self) -> Result<()> {
+    self.webview.edit(EditCommand::Paste)
+  }
+
+  /// Select all the content of the page, or of the focused editable element.
+  pub fn select_all(&self) -> Result<()> {
+    self.webview.edit(EditCommand::SelectAll)
+  }
+
   /// Get a list of cookies for specific url.
   pub fn cookies_for_url(&self, url: &str) -> Result<Vec<cookie::Cookie<'static>>> {
     self.webview.cookies_for_url(url)
@@ -1597,11 +2864,41 @@ impl WebView {
     self.webview.load_url_with_headers(url, headers)
   }
 
+  /// Navigate to the specified url using the specified cache policy.
+  ///
+  /// With [`CachePolicy::BypassCache`], only the main document skips the cache, and custom
+  /// protocol handlers receive its request with `Cache-Control: no-cache` and `Pragma: no-cache` headers.
+  pub fn load_url_with(&self, url: &str, cache_policy: CachePolicy) -> Result<()> {
+    self.webview.load_url_with(url, cache_policy)
+  }
+
+  /// Reload the current page, fetching its resources from the origin instead of the cache.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Clears the in-memory resource cache first, which is shared by all the webviews of the app.
+  pub fn reload_bypassing_cache(&self) -> Result<()> {
+    self.webview.reload_bypassing_cache()
+  }
+
   /// Load html content into the webview
   pub fn load_html(&self, html: &str) -> Result<()> {
     self.webview.load_html(html)
   }
 
+  /// Close the webview now, which tears down the native webview and releases the engine
+  /// resources it holds.
+  ///
+  /// Dropping the [`WebView`] does the same, this also reports the errors from the engine.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: If the window is destroyed first, the webview is closed with it and
+  ///   dropping the [`WebView`] afterwards is still safe.
+  pub fn close(self) -> Result<()> {
+    self.webview.close()
+  }
+
   /// Clear all browsing data
   pub fn clear_all_browsing_data(&self) -> Result<()> {
     self.webview.clear_all_browsing_data()
@@ -1624,6 +2921,30 @@ impl WebView {
     self.webview.set_visible(visible)
   }
 
+  /// Moves the webview above its sibling webviews and views in the parent window.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS**: Only effective if the webview was created as a child.
+  /// - **Linux**: Only effective if the webview was created as a child or in a container that
+  ///   stacks its children, such as [`gtk::Fixed`].
+  /// - **Android**: Unsupported.
+  pub fn raise(&self) -> Result<()> {
+    self.webview.raise()
+  }
+
+  /// Moves the webview below its sibling webviews and views in the parent window.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS**: Only effective if the webview was created as a child.
+  /// - **Linux**: Only effective if the webview was created as a child or in a container that
+  ///   stacks its children, such as [`gtk::Fixed`].
+  /// - **Android**: Unsupported.
+  pub fn lower(&self) -> Result<()> {
+    self.webview.lower()
+  }
+
   /// Try moving focus to the webview.
   pub fn focus(&self) -> Result<()> {
     self.webview.focus()
@@ -1694,6 +3015,12 @@ pub trait WebViewExtWindows {
   /// Returns WebView2 Controller
   fn controller(&self) -> ICoreWebView2Controller;
 
+  /// Returns the underlying WebView2 [`ICoreWebView2`], to call WebView2 APIs not exposed by wry.
+  fn webview(&self) -> ICoreWebView2;
+
+  /// Returns the WebView2 Environment this webview was created with.
+  fn environment(&self) -> ICoreWebView2Environment;
+
   /// Changes the webview2 theme.
   ///
   /// Requires WebView2 Runtime version 101.0.1210.39 or higher, returns error on older versions,
@@ -1716,6 +3043,61 @@ pub trait WebViewExtWindows {
 
   /// Attaches this webview to the given HWND and removes it from the current one.
   fn reparent(&self, hwnd: isize) -> Result<()>;
+
+  /// Returns the memory used by the browser, renderer, GPU and utility processes backing this webview.
+  ///
+  /// The processes are shared with other webviews created with the same data directory.
+  ///
+  /// Requires WebView2 Runtime version 1.0.1108.44 or higher, returns error on older versions,
+  /// see <https://learn.microsoft.com/en-us/microsoft-edge/webview2/reference/win32/icorewebview2environment8#getprocessinfos>
+  fn process_memory_usage(&self) -> Result<Vec<ProcessMemoryUsage>>;
+
+  /// Try to suspend the webview renderer to save CPU and memory, `handler` is called with whether
+  /// it was suspended. The webview is resumed automatically when it is made visible or when
+  /// a script is evaluated in it.
+  ///
+  /// The webview must be hidden with [`WebView::set_visible`] first, otherwise this returns an error.
+  ///
+  /// Requires WebView2 Runtime version 86.0.616.0 or higher, see <https://learn.microsoft.com/en-us/microsoft-edge/webview2/reference/win32/icorewebview2_3#trysuspend>
+  fn try_suspend<F: FnOnce(bool) + 'static>(&self, handler: F) -> Result<()>;
+
+  /// Resume a webview suspended with [`Self::try_suspend`].
+  fn resume(&self) -> Result<()>;
+
+  /// Whether the webview is suspended.
+  fn is_suspended(&self) -> Result<bool>;
+
+  /// Call a [Chrome DevTools Protocol](https://chromedevtools.github.io/devtools-protocol/) method,
+  /// e.g. `Network.enable`, with its parameters as a JSON object. `handler` is called with the
+  /// JSON result of the method.
+  ///
+  /// See <https://learn.microsoft.com/en-us/microsoft-edge/webview2/reference/win32/icorewebview2#calldevtoolsprotocolmethod>
+  fn call_devtools_protocol_method<F>(&self, method: &str, params: &str, handler: F) -> Result<()>
+  where
+    F: FnOnce(Result<String>) + 'static;
+
+  /// Subscribe to a Chrome DevTools Protocol event, e.g. `Network.requestWillBeSent`. `handler` is
+  /// called with the JSON parameters of every occurrence of the event.
+  ///
+  /// Most events are only sent after their domain was enabled with [`Self::call_devtools_protocol_method`].
+  fn add_devtools_protocol_event_handler<F>(&self, event: &str, handler: F) -> Result<()>
+  where
+    F: Fn(String) + 'static;
+
+  /// Serve the files of `folder` from `https://<host>`,
+  /// see [`WebViewBuilderExtWindows::with_virtual_host_name_to_folder_mapping`].
+  ///
+  /// The mapping only applies to navigations started after this call.
+  fn set_virtual_host_name_to_folder_mapping(
+    &self,
+    host: &str,
+    folder: &std::path::Path,
+    access: HostResourceAccessKind,
+  ) -> Result<()>;
+
+  /// Remove a mapping added with [`Self::set_virtual_host_name_to_folder_mapping`] or
+  /// [`WebViewBuilderExtWindows::with_virtual_host_name_to_folder_mapping`].
+  fn clear_virtual_host_name_to_folder_mapping(&self, host: &str) -> Result<()>;
 }
 
 #[cfg(target_os = "windows")]
@@ -1724,6 +3106,14 @@ impl WebViewExtWindows for WebView {
     self.webview.controller.clone()
   }
 
+  fn webview(&self) -> ICoreWebView2 {
+    self.webview.webview.clone()
+  }
+
+  fn environment(&self) -> ICoreWebView2Environment {
+    self.webview.env.clone()
+  }
+
   fn set_theme(&self, theme: Theme) -> Result<()> {
     self.webview.set_theme(theme)
   }
@@ -1735,6 +3125,55 @@ impl WebViewExtWindows for WebView {
   fn reparent(&self, hwnd: isize) -> Result<()> {
     self.webview.reparent(hwnd)
   }
+
+  fn process_memory_usage(&self) -> Result<Vec<ProcessMemoryUsage>> {
+    self.webview.process_memory_usage()
+  }
+
+  fn try_suspend<F: FnOnce(bool) + 'static>(&self, handler: F) -> Result<()> {
+    self.webview.try_suspend(handler)
+  }
+
+  fn resume(&self) -> Result<()> {
+    self.webview.resume()
+  }
+
+  fn is_suspended(&self) -> Result<bool> {
+    self.webview.is_suspended()
+  }
+
+  fn call_devtools_protocol_method<F>(&self, method: &str, params: &str, handler: F) -> Result<()>
+  where
+    F: FnOnce(Result<String>) + 'static,
+  {
+    self
+      .webview
+      .call_devtools_protocol_method(method, params, handler)
+  }
+
+  fn add_devtools_protocol_event_handler<F>(&self, event: &str, handler: F) -> Result<()>
+  where
+    F: Fn(String) + 'static,
+  {
+    self
+      .webview
+      .add_devtools_protocol_event_handler(event, handler)
+  }
+
+  fn set_virtual_host_name_to_folder_mapping(
+    &self,
+    host: &str,
+    folder: &std::path::Path,
+    access: HostResourceAccessKind,
+  ) -> Result<()> {
+    self
+      .webview
+      .set_virtual_host_name_to_folder_mapping(host, folder, access)
+  }
+
+  fn clear_virtual_host_name_to_folder_mapping(&self, host: &str) -> Result<()> {
+    self.webview.clear_virtual_host_name_to_folder_mapping(host)
+  }
 }
 
 /// Additional methods on `WebView` that are specific to Linux.
@@ -1742,7 +3181,8 @@ impl WebViewExtWindows for WebView {
 pub trait WebViewExtUnix: Sized {
   /// Create the webview inside a GTK container widget, such as GTK window.
   ///
-  /// - If the container is [`gtk::Box`], it is added using [`Box::pack_start(webview, true, true, 0)`](gtk::prelude::BoxExt::pack_start).
+  /// - If the container is [`gtk::Box`], it is added using [`Box::pack_start(webview, true, true, 0)`](gtk::prelude::BoxExt::pack_start),
+  ///   see [`WebViewBuilderExtUnix::with_gtk_box_packing`] to change how it shares the box with other widgets.
   /// - If the container is [`gtk::Fixed`], its [size request](gtk::prelude::WidgetExt::set_size_request) will be set using the (width, height) bounds passed in
   ///   and will be added to the container using [`Fixed::put`](gtk::prelude::FixedExt::put) using the (x, y) bounds passed in.
   /// - For all other containers, it will be added using [`gtk::prelude::ContainerExt::add`]
@@ -1761,6 +3201,14 @@ pub trait WebViewExtUnix: Sized {
   fn reparent<W>(&self, widget: &W) -> Result<()>
   where
     W: gtk::prelude::IsA<gtk::Container>;
+
+  /// Send a message to the web extensions of the page, received by their
+  /// `WebKitWebPage::user-message-received` handlers, and get their reply in `callback`.
+  ///
+  /// See [`WebContextExtUnix::set_web_extensions_directory`].
+  fn send_web_extension_message<F>(&self, message: &webkit2gtk::UserMessage, callback: F)
+  where
+    F: FnOnce(Result<webkit2gtk::UserMessage>) + 'static;
 }
 
 #[cfg(gtk)]
@@ -1782,6 +3230,36 @@ impl WebViewExtUnix for WebView {
   {
     self.webview.reparent(widget)
   }
+
+  fn send_web_extension_message<F>(&self, message: &webkit2gtk::UserMessage, callback: F)
+  where
+    F: FnOnce(Result<webkit2gtk::UserMessage>) + 'static,
+  {
+    self.webview.send_web_extension_message(message, callback)
+  }
+}
+
+/// Additional methods on [`WebContext`] that are specific to Linux.
+#[cfg(gtk)]
+pub trait WebContextExtUnix {
+  /// Load the WebKit web extensions in `directory`, shared libraries built against
+  /// `webkit2gtk-web-extension-4.1`, into the web process of the webviews created with this
+  /// context. They run next to the page and can reach its DOM for integrations that user
+  /// scripts can't do.
+  ///
+  /// Use [`WebViewExtUnix::send_web_extension_message`] and
+  /// [`WebViewBuilderExtUnix::with_web_extension_message_handler`] to talk to them.
+  ///
+  /// Must be called before the first webview of the context is created, since the extensions
+  /// are loaded when the web process starts.
+  fn set_web_extensions_directory(&mut self, directory: &std::path::Path);
+}
+
+#[cfg(gtk)]
+impl WebContextExtUnix for WebContext {
+  fn set_web_extensions_directory(&mut self, directory: &std::path::Path) {
+    self.os.set_web_extensions_directory(directory);
+  }
 }
 
 /// Additional methods on `WebView` that are specific to macOS.
@@ -1797,6 +3275,8 @@ pub trait WebViewExtMacOS {
   fn reparent(&self, window: *mut NSWindow) -> Result<()>;
   // Prints with extra options
   fn print_with_options(&self, options: &PrintOptions) -> Result<()>;
+  /// Changes the theme of the webview, which is what `prefers-color-scheme` reports to the page.
+  fn set_theme(&self, theme: Theme) -> Result<()>;
 }
 
 #[cfg(target_os = "macos")]
@@ -1820,6 +3300,11 @@ impl WebViewExtMacOS for WebView {
   fn print_with_options(&self, options: &PrintOptions) -> Result<()> {
     self.webview.print_with_options(options)
   }
+
+  fn set_theme(&self, theme: Theme) -> Result<()> {
+    self.webview.set_theme(theme);
+    Ok(())
+  }
 }
 
 /// Additional methods on `WebView` that are specific to iOS.
@@ -1871,6 +3356,31 @@ pub enum Theme {
 /// Each value can be 0..255 inclusive.
 pub type RGBA = (u8, u8, u8, u8);
 
+/// A key event received by [`WebViewBuilder::with_key_event_handler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+  /// The platform key code of the key.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: The [virtual key code](https://learn.microsoft.com/en-us/windows/win32/inputdev/virtual-key-codes).
+  /// - **macOS**: The [`keyCode`](https://developer.apple.com/documentation/appkit/nsevent/1534513-keycode) of the event.
+  /// - **Linux**: The GDK keyval, see [`gtk::gdk::keys::constants`].
+  pub native_key_code: u32,
+  /// Whether the key was pressed or released.
+  pub pressed: bool,
+  /// Whether this is an auto-repeat of a key that is held down.
+  pub repeat: bool,
+  /// Whether a Ctrl key is held down.
+  pub ctrl: bool,
+  /// Whether a Shift key is held down.
+  pub shift: bool,
+  /// Whether an Alt or Option key is held down.
+  pub alt: bool,
+  /// Whether a Cmd or Super key is held down.
+  pub meta: bool,
+}
+
 /// Type of of page loading event
 pub enum PageLoadEvent {
   /// Indicates that the content of the page has started loading
@@ -1879,6 +3389,138 @@ pub enum PageLoadEvent {
   Finished,
 }
 
+/// The format of a page saved with [`WebView::save_page`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveFormat {
+  /// A single MHTML file with the page and its resources.
+  ///
+  /// Supported on Windows and Linux.
+  Mhtml,
+  /// A web archive with the page and its resources, as saved by Safari.
+  ///
+  /// Supported on macOS and iOS.
+  WebArchive,
+  /// A PDF document of the page.
+  ///
+  /// Supported on Windows, macOS and iOS.
+  Pdf,
+}
+
+/// How a navigation started with [`WebView::load_url_with`] uses the cache.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CachePolicy {
+  /// Use cached responses as their caching headers allow.
+  #[default]
+  Default,
+  /// Ignore the cached response of the page itself and load it from the origin.
+  ///
+  /// Only the main document bypasses the cache, its subresources like scripts, styles and
+  /// images are still loaded as their caching headers allow. Use [`WebView::reload_bypassing_cache`]
+  /// to also refresh them.
+  BypassCache,
+}
+
+impl CachePolicy {
+  /// The request headers asking the network stack, and custom protocol handlers, to skip the cache.
+  #[cfg_attr(any(target_os = "macos", target_os = "ios"), allow(dead_code))]
+  pub(crate) fn request_headers(self) -> Option<http::HeaderMap> {
+    match self {
+      Self::Default => None,
+      Self::BypassCache => {
+        let mut headers = http::HeaderMap::new();
+        let no_cache = http::HeaderValue::from_static("no-cache");
+        headers.insert(http::header::CACHE_CONTROL, no_cache.clone());
+        headers.insert(http::header::PRAGMA, no_cache);
+        Some(headers)
+      }
+    }
+  }
+}
+
+/// The devices requested by the page, see [`WebViewBuilder::with_media_capture_permission_handler`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaCaptureKind {
+  /// A camera.
+  Camera,
+  /// A microphone.
+  Microphone,
+  /// Both a camera and a microphone at once.
+  CameraAndMicrophone,
+}
+
+/// The back/forward history of a webview, see [`WebView::back_forward_list`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackForwardList {
+  /// The pages before the current one, from the oldest to the most recent.
+  pub back: Vec<HistoryItem>,
+  /// The current page.
+  pub current: Option<HistoryItem>,
+  /// The pages after the current one, from the closest to the furthest.
+  pub forward: Vec<HistoryItem>,
+}
+
+/// A page of a [`BackForwardList`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryItem {
+  /// The URL of the page.
+  pub url: String,
+  /// The title of the page, empty if it has none.
+  pub title: String,
+}
+
+/// A page favicon, see [`WebViewBuilder::with_favicon_changed_handler`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Icon {
+  /// The pixels, row by row, as non-premultiplied RGBA with 8 bits per channel.
+  pub rgba: Vec<u8>,
+  /// The width in pixels.
+  pub width: u32,
+  /// The height in pixels.
+  pub height: u32,
+}
+
+/// A failed main frame navigation, see [`WebViewBuilder::with_navigation_failed_handler`].
+#[derive(Debug, Clone)]
+pub struct NavigationError {
+  /// The URL that failed to load.
+  pub url: String,
+  /// Why the navigation failed.
+  pub kind: NavigationErrorKind,
+}
+
+impl NavigationError {
+  /// Whether the engine shows its own error page for this error.
+  pub(crate) fn has_error_page(&self) -> bool {
+    !matches!(
+      self.kind,
+      NavigationErrorKind::HttpStatus(_) | NavigationErrorKind::Cancelled
+    )
+  }
+}
+
+/// The reason of a [`NavigationError`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationErrorKind {
+  /// The host name could not be resolved.
+  HostNotFound,
+  /// The server could not be reached or the connection was lost.
+  ConnectionFailed,
+  /// The TLS handshake failed, e.g. because of an invalid or expired certificate.
+  Tls,
+  /// The server responded with an HTTP error status code.
+  HttpStatus(u16),
+  /// The server did not respond in time.
+  Timeout,
+  /// The navigation was cancelled, e.g. by another navigation.
+  Cancelled,
+  /// Any other error.
+  Other,
+}
+
 #[cfg(any(
   target_os = "linux",
   target_os = "dragonfly",
@@ -1886,8 +3528,32 @@ pub enum PageLoadEvent {
   target_os = "netbsd",
   target_os = "openbsd",
 ))]
-#[derive(Default)]
-pub(crate) struct PlatformSpecificWebViewAttributes;
+pub(crate) struct PlatformSpecificWebViewAttributes {
+  box_expand: bool,
+  box_fill: bool,
+  box_padding: u32,
+  web_extension_message_handler: Option<Box<dyn Fn(&webkit2gtk::UserMessage) -> bool>>,
+  drag_region_target: drag_region::DragRegionTarget,
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd",
+))]
+impl Default for PlatformSpecificWebViewAttributes {
+  fn default() -> Self {
+    Self {
+      box_expand: true,
+      box_fill: true,
+      box_padding: 0,
+      web_extension_message_handler: None,
+      drag_region_target: Default::default(),
+    }
+  }
+}
 
 #[cfg(test)]
 mod tests {
@@ -1900,4 +3566,22 @@ mod tests {
       panic!("{}", error);
     }
   }
+
+  #[test]
+  fn validates_custom_protocol_names() {
+    assert!(validate_custom_protocol_name("wry").is_ok());
+    assert!(validate_custom_protocol_name("my-app+ipc.v2").is_ok());
+    assert!(matches!(
+      validate_custom_protocol_name("2fa"),
+      Err(Error::InvalidCustomProtocol(_))
+    ));
+    assert!(matches!(
+      validate_custom_protocol_name("my app"),
+      Err(Error::InvalidCustomProtocol(_))
+    ));
+    assert!(matches!(
+      validate_custom_protocol_name("HTTPS"),
+      Err(Error::ReservedCustomProtocol(_))
+    ));
+  }
 }
@@ -0,0 +1,217 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Blocking navigations outside of a set of URL patterns, see [`WebViewBuilder::with_navigation_allowlist`](crate::WebViewBuilder::with_navigation_allowlist),
+//! and handing the non-web schemes to the app, see [`WebViewBuilder::with_external_scheme_handler`](crate::WebViewBuilder::with_external_scheme_handler).
+
+use std::rc::Rc;
+
+/// Wraps a navigation or new window handler so that URLs matching none of the `allowlist`
+/// patterns are refused before `handler` is asked.
+pub(crate) fn wrap_handler(
+  allowlist: Vec<String>,
+  handler: Option<Box<dyn Fn(String) -> bool>>,
+) -> Box<dyn Fn(String) -> bool> {
+  Box::new(move |url| {
+    if !allowlist.iter().any(|pattern| matches(pattern, &url)) {
+      return false;
+    }
+    handler.as_ref().map_or(true, |handler| handler(url))
+  })
+}
+
+/// Schemes the webview loads itself, anything else is handed to the external scheme handler.
+const WEB_SCHEMES: &[&str] = &[
+  "http",
+  "https",
+  "about",
+  "data",
+  "blob",
+  "file",
+  "javascript",
+];
+
+/// Wraps a navigation or new window handler so that URLs with a scheme the webview can't load
+/// go to `external` instead, and are opened with the OS default handler if it returns `true`.
+///
+/// `custom_protocols` are the custom protocol names, which are loaded by the webview.
+pub(crate) fn wrap_external_scheme_handler(
+  custom_protocols: Vec<String>,
+  external: Rc<dyn Fn(String) -> bool>,
+  handler: Option<Box<dyn Fn(String) -> bool>>,
+) -> Box<dyn Fn(String) -> bool> {
+  Box::new(move |url| {
+    let scheme = url.split_once(':').map_or("", |(scheme, _)| scheme);
+    let is_web = WEB_SCHEMES
+      .iter()
+      .copied()
+      .chain(custom_protocols.iter().map(String::as_str))
+      .any(|s| s.eq_ignore_ascii_case(scheme));
+    if !is_web {
+      if external(url.clone()) {
+        crate::open_with_default_handler(&url);
+      }
+      return false;
+    }
+    handler.as_ref().map_or(true, |handler| handler(url))
+  })
+}
+
+/// Whether `url` starts with a scheme as defined by RFC 3986, `ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )`,
+/// at least two characters long so that a Windows path like `C:\file` isn't taken for a URL.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(crate) fn has_valid_scheme(url: &str) -> bool {
+  let Some((scheme, _)) = url.split_once(':') else {
+    return false;
+  };
+  scheme.len() >= 2
+    && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+    && scheme
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Match `url` against `pattern`.
+///
+/// Patterns with an authority, like `https://*.example/*`, are compared part by part so that a
+/// `*` can't reach into another part: the scheme exactly, the host exactly or, with a leading
+/// `*.`, as one of its subdomains, the port exactly or any with `:*`, and the path and query
+/// with `*` matching any sequence of characters. A pattern without a path matches any path.
+///
+/// Other patterns, like `about:blank`, match the scheme exactly and the rest of the url
+/// with `*` matching any sequence of characters.
+fn matches(pattern: &str, url: &str) -> bool {
+  // `split` always yields at least one item
+  let url = url.split('#').next().unwrap();
+  let Some((scheme, rest)) = pattern.split_once(':') else {
+    return false;
+  };
+
+  let Some(authority_and_path) = rest.strip_prefix("//") else {
+    return url.split_once(':').is_some_and(|(url_scheme, url_rest)| {
+      url_scheme.eq_ignore_ascii_case(scheme) && glob(rest, url_rest)
+    });
+  };
+
+  let Ok(uri) = url.parse::<http::Uri>() else {
+    return false;
+  };
+  let (Some(url_scheme), Some(url_host)) = (uri.scheme_str(), uri.host()) else {
+    return false;
+  };
+  if !url_scheme.eq_ignore_ascii_case(scheme) {
+    return false;
+  }
+
+  let (authority, path) = match authority_and_path.find('/') {
+    Some(i) => authority_and_path.split_at(i),
+    None => (authority_and_path, ""),
+  };
+  let (host, port) = match authority.rsplit_once(':') {
+    Some((host, port)) if !port.ends_with(']') => (host, Some(port)),
+    _ => (authority, None),
+  };
+
+  let host_matches = match host.strip_prefix("*.") {
+    _ if host == "*" => true,
+    Some(suffix) => {
+      let url_host = url_host.to_ascii_lowercase();
+      let suffix = format!(".{}", suffix.to_ascii_lowercase());
+      !suffix.contains('*') && url_host.len() > suffix.len() && url_host.ends_with(&suffix)
+    }
+    None => !host.contains('*') && url_host.eq_ignore_ascii_case(host),
+  };
+  let port_matches = match port {
+    Some("*") => true,
+    Some(port) => uri.port().is_some_and(|p| p.as_str() == port),
+    None => uri.port().is_none(),
+  };
+  let path_matches =
+    path.is_empty() || glob(path, uri.path_and_query().map_or("/", |path| path.as_str()));
+
+  host_matches && port_matches && path_matches
+}
+
+/// Match `text` against `pattern`, where `*` matches any sequence of characters.
+fn glob(pattern: &str, text: &str) -> bool {
+  let mut parts = pattern.split('*');
+  // `split` always yields at least one item
+  let first = parts.next().unwrap();
+  let Some(mut rest) = text.strip_prefix(first) else {
+    return false;
+  };
+
+  let mut parts = parts.peekable();
+  while let Some(part) = parts.next() {
+    if parts.peek().is_none() {
+      // the pattern ends with this part, so the text has to end with it too
+      return rest.ends_with(part);
+    }
+    match rest.find(part) {
+      Some(i) => rest = &rest[i + part.len()..],
+      None => return false,
+    }
+  }
+
+  // no `*` in the pattern
+  rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn validates_schemes() {
+    assert!(has_valid_scheme("mailto:wry@tauri.app"));
+    assert!(has_valid_scheme("ms-settings:display"));
+    assert!(has_valid_scheme("web+app:open"));
+    assert!(!has_valid_scheme("C:\\Windows\\notepad.exe"));
+    assert!(!has_valid_scheme("\\\\server\\share:stream"));
+    assert!(!has_valid_scheme("1tel:123"));
+    assert!(!has_valid_scheme("no scheme"));
+    assert!(!has_valid_scheme(":empty"));
+  }
+
+  #[test]
+  fn matches_url_patterns() {
+    assert!(matches("https://app.example/*", "https://app.example/"));
+    assert!(matches(
+      "https://app.example/*",
+      "https://app.example/a/b?c=d"
+    ));
+    assert!(!matches(
+      "https://app.example/*",
+      "https://app.example.evil/"
+    ));
+    assert!(matches("wry://*", "wry://localhost/index.html"));
+    assert!(matches("https://*.example/*", "https://cdn.example/x"));
+    assert!(!matches("https://*.example/*", "http://cdn.example/x"));
+    assert!(matches("about:blank", "about:blank"));
+    assert!(!matches("about:blank", "about:blankx"));
+    assert!(matches(
+      "http://wry.localhost/*",
+      "http://wry.localhost/a#b"
+    ));
+    assert!(matches("http://localhost:*/*", "http://localhost:1430/"));
+    assert!(!matches("http://localhost/*", "http://localhost:1430/"));
+  }
+
+  #[test]
+  fn wildcards_stay_within_their_part() {
+    assert!(!matches(
+      "https://*.example/*",
+      "https://evil.com/x.example/"
+    ));
+    assert!(!matches("https://*.example/*", "https://example/"));
+    assert!(!matches("https://*.example/*", "https://evilexample/"));
+    assert!(!matches("http://wry.*", "http://wry.evil.com/"));
+    assert!(!matches("http://wry.localhost/*", "http://wry.evil.com/"));
+    assert!(!matches(
+      "https://app.example/*",
+      "https://app.example@evil.com/"
+    ));
+    assert!(!matches("https://app.example", "https://app.example.evil/"));
+  }
+}
@@ -0,0 +1,79 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::rc::Rc;
+
+use http::Request;
+
+use crate::{RequestAsyncResponder, WebViewId};
+
+/// A reusable piece of functionality that hooks into the creation of a webview.
+///
+/// Plugins are added with [`WebViewBuilder::with_plugin`](crate::WebViewBuilder::with_plugin)
+/// and compose with each other and with the options set directly on the builder:
+/// their scripts and protocols are registered next to the builder ones, and they
+/// get to see the IPC messages before the IPC handler.
+///
+/// ```no_run
+/// use wry::{http::Request, Plugin};
+///
+/// struct Logger;
+///
+/// impl Plugin for Logger {
+///   fn initialization_scripts(&self) -> Vec<String> {
+///     vec!["window.addEventListener('error', (e) => window.ipc.postMessage('log:' + e.message))".into()]
+///   }
+///
+///   fn on_ipc_message(&self, request: &Request<String>) -> bool {
+///     if let Some(message) = request.body().strip_prefix("log:") {
+///       println!("{message}");
+///       return true;
+///     }
+///     false
+///   }
+/// }
+/// ```
+pub trait Plugin {
+  /// The scripts to inject, see [`WebViewBuilder::with_initialization_script`](crate::WebViewBuilder::with_initialization_script).
+  fn initialization_scripts(&self) -> Vec<String> {
+    Vec::new()
+  }
+
+  /// The custom protocols to register, see [`WebViewBuilder::with_asynchronous_custom_protocol`](crate::WebViewBuilder::with_asynchronous_custom_protocol).
+  ///
+  /// Building the webview fails with [`Error::DuplicateCustomProtocol`](crate::Error::DuplicateCustomProtocol)
+  /// if a protocol was already registered by the builder or another plugin.
+  fn custom_protocols(
+    &self,
+  ) -> Vec<(
+    String,
+    Box<dyn Fn(WebViewId, Request<Vec<u8>>, RequestAsyncResponder)>,
+  )> {
+    Vec::new()
+  }
+
+  /// Called with every message posted with `window.ipc.postMessage`, in the order the plugins
+  /// were added. Return `true` to consume the message so that it does not reach the next
+  /// plugins and the IPC handler.
+  fn on_ipc_message(&self, request: &Request<String>) -> bool {
+    let _ = request;
+    false
+  }
+}
+
+/// Wraps the IPC handler so that `plugins` see the messages first.
+pub(crate) fn wrap_ipc_handler(
+  plugins: Vec<Rc<dyn Plugin>>,
+  handler: Option<Box<dyn Fn(Request<String>)>>,
+) -> Box<dyn Fn(Request<String>)> {
+  Box::new(move |request| {
+    if plugins.iter().any(|p| p.on_ipc_message(&request)) {
+      return;
+    }
+
+    if let Some(handler) = &handler {
+      handler(request);
+    }
+  })
+}
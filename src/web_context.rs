@@ -6,10 +6,20 @@
 use crate::webkitgtk::WebContextImpl;
 
 use std::{
+  borrow::Cow,
   collections::HashSet,
   path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+  },
+  time::{Duration, Instant},
 };
 
+use http::{Request, Response};
+
+use crate::{RequestAsyncResponder, WebViewId};
+
 /// A context that is shared between multiple [`WebView`]s.
 ///
 /// A browser would have a context for all the normal tabs and a different context for all the
@@ -28,6 +38,13 @@ pub struct WebContext {
   pub(crate) os: WebContextImpl,
   #[allow(dead_code)] // It's not needed on Windows and macOS.
   pub(crate) custom_protocols: HashSet<String>,
+  #[allow(dead_code)] // It's only used on Windows.
+  pub(crate) host_resolver_rules: Vec<(String, String)>,
+  /// The identifier and JSON source of the content rule lists, see [`Self::add_content_filter`].
+  #[allow(dead_code)] // It's not needed on Windows and Android.
+  pub(crate) content_filters: Vec<(String, String)>,
+  process_model: ProcessModel,
+  pub(crate) metrics: Arc<MetricsCounters>,
 }
 
 impl WebContext {
@@ -41,6 +58,10 @@ impl WebContext {
       os: WebContextImpl::new(data_directory.as_deref()),
       data_directory,
       custom_protocols: Default::default(),
+      host_resolver_rules: Default::default(),
+      content_filters: Default::default(),
+      process_model: Default::default(),
+      metrics: Default::default(),
     }
   }
 
@@ -50,6 +71,10 @@ impl WebContext {
       os: WebContextImpl::new_ephemeral(),
       data_directory: None,
       custom_protocols: Default::default(),
+      host_resolver_rules: Default::default(),
+      content_filters: Default::default(),
+      process_model: Default::default(),
+      metrics: Default::default(),
     }
   }
 
@@ -79,6 +104,134 @@ impl WebContext {
   pub fn set_allows_automation(&mut self, flag: bool) {
     self.os.set_allows_automation(flag);
   }
+
+  /// Resolve each hostname to the given address instead of asking the DNS, like Chromium's
+  /// `--host-resolver-rules`, e.g. `&[("api.example.com", "127.0.0.1")]` to point a staging
+  /// build at a local server. The address can also be another hostname, and the hostname can
+  /// use `*` wildcards. Replaces the rules set before, and only applies to the webviews created
+  /// afterwards.
+  ///
+  /// Fails with [`Error::InvalidHostResolverRule`](crate::Error::InvalidHostResolverRule), keeping
+  /// the previous rules, if a hostname or an address is empty or contains whitespace, quotes or commas.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The rules are passed to the browser process, so all the webviews sharing
+  ///   the same data directory must be created with the same rules.
+  /// - **macOS / Linux / iOS / Android**: Unsupported, the engines have no host mapping and
+  ///   HTTP(S) requests can't be intercepted.
+  pub fn set_host_resolver_rules(&mut self, rules: &[(&str, &str)]) -> crate::Result<()> {
+    let invalid = rules
+      .iter()
+      .flat_map(|(host, address)| [host, address])
+      .find(|entry| !is_valid_host_resolver_entry(entry));
+    if let Some(entry) = invalid {
+      return Err(crate::Error::InvalidHostResolverRule(entry.to_string()));
+    }
+
+    self.host_resolver_rules = rules
+      .iter()
+      .map(|(host, address)| (host.to_string(), address.to_string()))
+      .collect();
+    Ok(())
+  }
+
+  /// Block or alter requests of the webviews created with this context afterwards, with a
+  /// [content blocker](https://developer.apple.com/documentation/safariservices/creating-a-content-blocker)
+  /// rule list in JSON, e.g. to block ads and trackers. The engine compiles the rules into
+  /// a fast matcher, which is much cheaper than inspecting every request from Rust.
+  ///
+  /// The rules are compiled when each webview is created, which loads its first page once
+  /// they're ready so that it's filtered too. A rule list that fails to compile is ignored.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Compiled rule lists are cached in the `content-filters` folder of the
+  ///   data directory, or of the temporary directory without one.
+  /// - **macOS / iOS**: Compiled rule lists are cached in the default store of WebKit and
+  ///   only compiled again when the rules change.
+  /// - **Windows / Android**: Unsupported.
+  pub fn add_content_filter(&mut self, json_rules: impl Into<String>) {
+    let json_rules = json_rules.into();
+    let identifier = format!("wry-{:016x}", fnv1a(json_rules.as_bytes()));
+    if !self.content_filters.iter().any(|(id, _)| id == &identifier) {
+      self.content_filters.push((identifier, json_rules));
+    }
+  }
+
+  /// Choose whether the webviews created with this context afterwards share a web process,
+  /// trading isolation for memory. Defaults to [`ProcessModel::PerWebView`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: With [`ProcessModel::Shared`], the webviews are created as related views of
+  ///   a live webview of the context.
+  /// - **Windows**: [`ProcessModel::Shared`] limits the renderer processes of the browser
+  ///   process to one, a soft limit that cross-site frames can still exceed. All the webviews
+  ///   sharing the same data directory must be created with the same process model.
+  /// - **macOS / iOS / Android**: Unsupported, the system decides.
+  pub fn set_process_model(&mut self, process_model: ProcessModel) {
+    self.process_model = process_model;
+  }
+
+  /// The process model of this context, see [`Self::set_process_model`].
+  pub fn process_model(&self) -> ProcessModel {
+    self.process_model
+  }
+
+  /// Wipe the data directory of this context and start over with an empty profile.
+  ///
+  /// Use this to recover when the engine fails to start because the profile in the data
+  /// directory got corrupted, e.g. after a crash during a write: reset the data and build
+  /// the webview again. Cookies, local storage, caches and every other piece of web data
+  /// of the context are lost. Does nothing if the context uses the default data directory.
+  ///
+  /// This must only be called when no [`WebView`] created with this context is alive.
+  ///
+  /// [`WebView`]: crate::WebView
+  pub fn reset_data(&mut self) -> crate::Result<()> {
+    let Some(data_directory) = &self.data_directory else {
+      return Ok(());
+    };
+
+    match std::fs::remove_dir_all(data_directory) {
+      Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+      _ => {}
+    }
+    std::fs::create_dir_all(data_directory)?;
+
+    // The engine context keeps the old profile loaded, so it has to be recreated as well.
+    self.os.reset(data_directory);
+    self.custom_protocols.clear();
+
+    Ok(())
+  }
+
+  /// Get a snapshot of the custom protocol and IPC counters of all the webviews
+  /// created with this context.
+  ///
+  /// [`WebContextMetrics::ipc_messages_per_sec`] is measured since the previous call to this
+  /// function, so poll it at a regular interval to get a meaningful rate.
+  pub fn metrics(&self) -> WebContextMetrics {
+    self.metrics.snapshot()
+  }
+}
+
+/// Whether `entry` can be a hostname or an address of [`WebContext::set_host_resolver_rules`],
+/// the rules are joined with commas into a single quoted command line argument.
+fn is_valid_host_resolver_entry(entry: &str) -> bool {
+  !entry.is_empty()
+    && !entry
+      .chars()
+      .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '"' | '\'' | ','))
+}
+
+/// The 64-bit FNV-1a hash of `bytes`. Unlike `DefaultHasher`, it doesn't change between
+/// Rust versions, so the rule lists compiled by a previous run keep their identifier.
+fn fnv1a(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+    (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+  })
 }
 
 impl Default for WebContext {
@@ -87,6 +240,20 @@ impl Default for WebContext {
   }
 }
 
+/// How the webviews of a [`WebContext`] are spread over web processes.
+///
+/// See [`WebContext::set_process_model`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProcessModel {
+  /// Every webview gets its own web process, so a crash or a busy page only affects
+  /// its own webview.
+  #[default]
+  PerWebView,
+  /// The webviews share a web process, which saves memory for apps with many windows.
+  Shared,
+}
+
 #[cfg(not(gtk))]
 #[derive(Debug)]
 pub(crate) struct WebContextImpl;
@@ -98,4 +265,158 @@ impl WebContextImpl {
   }
 
   fn set_allows_automation(&mut self, _flag: bool) {}
+
+  fn reset(&mut self, _: &Path) {}
+}
+
+/// A snapshot of the custom protocol and IPC activity of a [`WebContext`].
+///
+/// See [`WebContext::metrics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WebContextMetrics {
+  /// Number of custom protocol requests that were responded to.
+  pub protocol_requests: u64,
+  /// Total size in bytes of the custom protocol response bodies.
+  pub protocol_bytes: u64,
+  /// Average time between a custom protocol request and its response.
+  pub average_protocol_latency: Duration,
+  /// Number of custom protocol requests still waiting for their [`RequestAsyncResponder`].
+  pub pending_responders: u64,
+  /// Total number of IPC messages received from the webviews.
+  pub ipc_messages: u64,
+  /// IPC messages received per second since the previous snapshot.
+  pub ipc_messages_per_sec: f64,
+}
+
+#[derive(Debug)]
+pub(crate) struct MetricsCounters {
+  protocol_requests: AtomicU64,
+  protocol_bytes: AtomicU64,
+  protocol_latency_micros: AtomicU64,
+  pending_responders: AtomicU64,
+  ipc_messages: AtomicU64,
+  last_ipc_sample: Mutex<(Instant, u64)>,
+}
+
+impl Default for MetricsCounters {
+  fn default() -> Self {
+    Self {
+      protocol_requests: Default::default(),
+      protocol_bytes: Default::default(),
+      protocol_latency_micros: Default::default(),
+      pending_responders: Default::default(),
+      ipc_messages: Default::default(),
+      last_ipc_sample: Mutex::new((Instant::now(), 0)),
+    }
+  }
+}
+
+impl MetricsCounters {
+  fn snapshot(&self) -> WebContextMetrics {
+    let protocol_requests = self.protocol_requests.load(Ordering::Relaxed);
+    let latency = self.protocol_latency_micros.load(Ordering::Relaxed);
+    let ipc_messages = self.ipc_messages.load(Ordering::Relaxed);
+
+    let ipc_messages_per_sec = {
+      let mut last = self.last_ipc_sample.lock().unwrap();
+      let now = Instant::now();
+      let elapsed = now.duration_since(last.0).as_secs_f64();
+      let rate = if elapsed > 0. {
+        ipc_messages.saturating_sub(last.1) as f64 / elapsed
+      } else {
+        0.
+      };
+      *last = (now, ipc_messages);
+      rate
+    };
+
+    WebContextMetrics {
+      protocol_requests,
+      protocol_bytes: self.protocol_bytes.load(Ordering::Relaxed),
+      average_protocol_latency: Duration::from_micros(
+        latency.checked_div(protocol_requests).unwrap_or_default(),
+      ),
+      pending_responders: self.pending_responders.load(Ordering::Relaxed),
+      ipc_messages,
+      ipc_messages_per_sec,
+    }
+  }
+
+  /// Wraps a custom protocol handler so that its responses are recorded.
+  #[allow(clippy::type_complexity)]
+  pub(crate) fn wrap_protocol(
+    self: &Arc<Self>,
+    handler: Box<dyn Fn(WebViewId, Request<Vec<u8>>, RequestAsyncResponder)>,
+  ) -> Box<dyn Fn(WebViewId, Request<Vec<u8>>, RequestAsyncResponder)> {
+    let metrics = self.clone();
+    Box::new(move |id, request, responder| {
+      let start = Instant::now();
+      let pending = PendingResponder::new(metrics.clone());
+      let responder = RequestAsyncResponder {
+        responder: Box::new(move |response: Response<Cow<'static, [u8]>>| {
+          let metrics = &pending.0;
+          metrics.protocol_requests.fetch_add(1, Ordering::Relaxed);
+          metrics
+            .protocol_bytes
+            .fetch_add(response.body().len() as u64, Ordering::Relaxed);
+          metrics
+            .protocol_latency_micros
+            .fetch_add(start.elapsed().as_micros() as u64, Ordering::Relaxed);
+          (responder.responder)(response);
+        }),
+      };
+      handler(id, request, responder)
+    })
+  }
+
+  /// Wraps the IPC handler so that incoming messages are counted.
+  pub(crate) fn wrap_ipc(
+    self: &Arc<Self>,
+    handler: Box<dyn Fn(Request<String>)>,
+  ) -> Box<dyn Fn(Request<String>)> {
+    let metrics = self.clone();
+    Box::new(move |request| {
+      metrics.ipc_messages.fetch_add(1, Ordering::Relaxed);
+      handler(request)
+    })
+  }
+}
+
+/// Keeps a custom protocol request counted as pending until its responder is consumed or dropped.
+struct PendingResponder(Arc<MetricsCounters>);
+
+impl PendingResponder {
+  fn new(metrics: Arc<MetricsCounters>) -> Self {
+    metrics.pending_responders.fetch_add(1, Ordering::Relaxed);
+    Self(metrics)
+  }
+}
+
+impl Drop for PendingResponder {
+  fn drop(&mut self) {
+    self.0.pending_responders.fetch_sub(1, Ordering::Relaxed);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn validates_host_resolver_entries() {
+    assert!(is_valid_host_resolver_entry("api.example.com"));
+    assert!(is_valid_host_resolver_entry("*.test"));
+    assert!(is_valid_host_resolver_entry("[::1]:8080"));
+    assert!(!is_valid_host_resolver_entry(""));
+    assert!(!is_valid_host_resolver_entry("127.0.0.1\" --no-sandbox"));
+    assert!(!is_valid_host_resolver_entry("a.test,b.test"));
+    assert!(!is_valid_host_resolver_entry("127.0.0.1 EXCLUDE"));
+  }
+
+  #[test]
+  fn hashes_with_fnv1a() {
+    assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+  }
 }
@@ -20,18 +20,24 @@ use raw_window_handle::{HasWindowHandle, RawWindowHandle};
 #[cfg(any(debug_assertions, feature = "devtools"))]
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::{
+  cell::{Cell, RefCell},
+  collections::HashSet,
   ffi::c_ulong,
+  path::Path,
+  rc::Rc,
   sync::{Arc, Mutex},
 };
 #[cfg(any(debug_assertions, feature = "devtools"))]
 use webkit2gtk::WebInspectorExt;
 use webkit2gtk::{
-  AutoplayPolicy, CookieManagerExt, InputMethodContextExt, LoadEvent, NavigationPolicyDecision,
-  NavigationPolicyDecisionExt, NetworkProxyMode, NetworkProxySettings, PolicyDecisionType,
-  PrintOperationExt, SettingsExt, URIRequest, URIRequestExt, UserContentInjectedFrames,
-  UserContentManager, UserContentManagerExt, UserScript, UserScriptInjectionTime,
-  WebContextExt as Webkit2gtkWeContextExt, WebView, WebViewExt, WebsiteDataManagerExt,
-  WebsiteDataManagerExtManual, WebsitePolicies,
+  AutoplayPolicy, BackForwardListExt, BackForwardListItemExt, CookieManagerExt,
+  InputMethodContextExt, LoadEvent, NavigationPolicyDecision, NavigationPolicyDecisionExt,
+  NetworkProxyMode, NetworkProxySettings, PermissionRequestExt, PolicyDecisionType,
+  PrintOperationExt, SettingsExt, URIRequest, URIRequestExt, URIResponseExt,
+  UserContentInjectedFrames, UserContentManager, UserContentManagerExt, UserMediaPermissionRequest,
+  UserMediaPermissionRequestExt, UserScript, UserScriptInjectionTime,
+  WebContextExt as Webkit2gtkWeContextExt, WebResourceExt, WebView, WebViewExt,
+  WebsiteDataManagerExt, WebsiteDataManagerExtManual, WebsitePolicies,
 };
 use webkit2gtk_sys::{
   webkit_get_major_version, webkit_get_micro_version, webkit_get_minor_version,
@@ -42,8 +48,10 @@ use x11_dl::xlib::*;
 pub use web_context::WebContextImpl;
 
 use crate::{
-  proxy::ProxyConfig, web_context::WebContext, Error, PageLoadEvent, Rect, Result,
-  WebViewAttributes, RGBA,
+  drag_region::DragRegionAction, proxy::ProxyConfig, web_context::WebContext, BackForwardList,
+  CachePolicy, EditCommand, Error, HistoryItem, Icon, KeyEvent, MediaCaptureKind, NavigationError,
+  NavigationErrorKind, PageLoadEvent, ProcessModel, Rect, Result, SaveFormat, WebViewAttributes,
+  RGBA,
 };
 
 use self::web_context::WebContextExt;
@@ -209,7 +217,7 @@ impl InnerWebView {
   pub fn new_gtk<W>(
     container: &W,
     mut attributes: WebViewAttributes,
-    _pl_attrs: super::PlatformSpecificWebViewAttributes,
+    mut pl_attrs: super::PlatformSpecificWebViewAttributes,
   ) -> Result<Self>
   where
     W: IsA<gtk::Container>,
@@ -243,6 +251,11 @@ impl InnerWebView {
     }
 
     let webview = Self::create_webview(web_context, &attributes);
+    if web_context.process_model() == ProcessModel::Shared
+      && web_context.os.shared_process_view().is_none()
+    {
+      web_context.os.set_shared_process_view(&webview);
+    }
 
     // Transparent
     if attributes.transparent {
@@ -265,9 +278,24 @@ impl InnerWebView {
     // Webview handlers
     Self::attach_handlers(&webview, web_context, &mut attributes);
 
+    // Drag regions
+    if attributes.drag_regions {
+      let webview = webview.downgrade();
+      let _ = pl_attrs.drag_region_target.set(Box::new(move |action| {
+        if let Some(webview) = webview.upgrade() {
+          perform_drag_region_action(&webview, action);
+        }
+      }));
+    }
+
     // IPC handler
     Self::attach_ipc_handler(webview.clone(), &mut attributes);
 
+    // Web extension messages
+    if let Some(handler) = pl_attrs.web_extension_message_handler.take() {
+      webview.connect_user_message_received(move |_, message| handler(message));
+    }
+
     // Drag drop handler
     if let Some(drag_drop_handler) = attributes.drag_drop_handler.take() {
       drag_drop::connect_drag_event(&webview, drag_drop_handler);
@@ -275,7 +303,7 @@ impl InnerWebView {
 
     web_context.register_automation(webview.clone());
 
-    let is_in_fixed_parent = Self::add_to_container(&webview, container, &attributes);
+    let is_in_fixed_parent = Self::add_to_container(&webview, container, &attributes, &pl_attrs);
 
     #[cfg(any(debug_assertions, feature = "devtools"))]
     let is_inspector_open = Self::attach_inspector_handlers(&webview);
@@ -299,7 +327,10 @@ impl InnerWebView {
     };
 
     // Initialize message handler
-    w.init("Object.defineProperty(window, 'ipc', { value: Object.freeze({ postMessage: function(x) { window.webkit.messageHandlers['ipc'].postMessage(x) } }) })")?;
+    w.init(&crate::ipc::init_script(
+      attributes.ipc_origins.as_deref(),
+      "Object.defineProperty(window, 'ipc', { value: Object.freeze({ postMessage: function(x) { window.webkit.messageHandlers['ipc'].postMessage(x) } }) })",
+    ))?;
 
     // Initialize scripts
     for js in attributes.initialization_scripts {
@@ -325,8 +356,35 @@ impl InnerWebView {
       web_context.register_uri_scheme(&name, handler)?;
     }
 
-    // Navigation
-    if let Some(url) = attributes.url {
+    // Navigation, once the content filters are ready so that the first page is filtered too
+    let manager = w
+      .webview
+      .user_content_manager()
+      .filter(|_| !web_context.content_filters.is_empty());
+    if let Some(manager) = manager {
+      let navigate: Box<dyn FnOnce()> = if let Some(url) = attributes.url {
+        let load_uri = web_context.os.deferred_load_uri();
+        let webview = w.webview.clone();
+        let headers = attributes.headers;
+        Box::new(move || load_uri(webview, url, headers))
+      } else if let Some(html) = attributes.html {
+        let webview = w.webview.clone();
+        Box::new(move || webview.load_html(&html, None))
+      } else {
+        Box::new(|| ())
+      };
+      let store_path = web_context
+        .data_directory()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| std::env::temp_dir().join("wry"))
+        .join("content-filters");
+      ffi::add_content_filters(
+        &manager,
+        store_path.to_string_lossy().into_owned(),
+        web_context.content_filters.clone().into_iter(),
+        navigate,
+      );
+    } else if let Some(url) = attributes.url {
       web_context.queue_load_uri(w.webview.clone(), url, attributes.headers);
       web_context.flush_queue_loader();
     } else if let Some(html) = attributes.html {
@@ -350,6 +408,12 @@ impl InnerWebView {
       .web_context(web_context.context())
       .is_controlled_by_automation(web_context.allows_automation());
 
+    if web_context.process_model() == ProcessModel::Shared {
+      if let Some(related_view) = web_context.os.shared_process_view() {
+        builder = builder.related_view(&related_view);
+      }
+    }
+
     if attributes.autoplay {
       builder = builder.website_policies(
         &WebsitePolicies::builder()
@@ -384,12 +448,24 @@ impl InnerWebView {
         settings.set_javascript_can_access_clipboard(true);
       }
 
+      settings.set_enable_fullscreen(attributes.fullscreen_content_allowed);
+      settings.set_enable_javascript_markup(attributes.javascript_enabled);
+
+      // getUserMedia is off by default, turn it on when the host decides the permission requests
+      if attributes.media_capture_permission_handler.is_some() {
+        settings.set_enable_media_stream(true);
+      }
+
       // Enable App cache
       settings.set_enable_page_cache(true);
 
       // Set user agent
       settings.set_user_agent(attributes.user_agent.as_deref());
 
+      if let Some(size) = attributes.minimum_font_size {
+        settings.set_minimum_font_size(size);
+      }
+
       // Devtools
       if attributes.devtools {
         settings.set_enable_developer_extras(true);
@@ -416,6 +492,113 @@ impl InnerWebView {
       });
     }
 
+    // Key event handler
+    if let Some(key_event_handler) = attributes.key_event_handler.take() {
+      let pressed_keys = Rc::new(RefCell::new(HashSet::new()));
+
+      let on_key_event = move |event: &gdk::EventKey, pressed: bool| {
+        let keyval = *event.keyval();
+        let repeat = if pressed {
+          !pressed_keys.borrow_mut().insert(keyval)
+        } else {
+          pressed_keys.borrow_mut().remove(&keyval);
+          false
+        };
+
+        let state = event.state();
+        let handled = key_event_handler(KeyEvent {
+          native_key_code: keyval,
+          pressed,
+          repeat,
+          ctrl: state.contains(gdk::ModifierType::CONTROL_MASK),
+          shift: state.contains(gdk::ModifierType::SHIFT_MASK),
+          alt: state.contains(gdk::ModifierType::MOD1_MASK),
+          meta: state.intersects(gdk::ModifierType::SUPER_MASK | gdk::ModifierType::META_MASK),
+        });
+
+        if handled {
+          glib::Propagation::Stop
+        } else {
+          glib::Propagation::Proceed
+        }
+      };
+      let on_key_event = Rc::new(on_key_event);
+      let on_key_press = on_key_event.clone();
+      webview.connect_key_press_event(move |_, event| on_key_press(event, true));
+      webview.connect_key_release_event(move |_, event| on_key_event(event, false));
+    }
+
+    // Focus changed handler
+    if let Some(focus_changed_handler) = attributes.focus_changed_handler.take() {
+      let focus_changed_handler = Rc::new(focus_changed_handler);
+      let focus_changed_handler_ = focus_changed_handler.clone();
+      webview.connect_focus_in_event(move |_, _| {
+        focus_changed_handler_(true);
+        glib::Propagation::Proceed
+      });
+      webview.connect_focus_out_event(move |_, _| {
+        focus_changed_handler(false);
+        glib::Propagation::Proceed
+      });
+    }
+
+    // Media capture permission handler
+    if let Some(media_capture_permission_handler) =
+      attributes.media_capture_permission_handler.take()
+    {
+      webview.connect_permission_request(move |webview, request| {
+        let Some(request) = request.downcast_ref::<UserMediaPermissionRequest>() else {
+          return false;
+        };
+
+        let kind = match (request.is_for_video_device(), request.is_for_audio_device()) {
+          (true, true) => MediaCaptureKind::CameraAndMicrophone,
+          (true, false) => MediaCaptureKind::Camera,
+          (false, true) => MediaCaptureKind::Microphone,
+          // screen capture and other devices keep the default behavior
+          (false, false) => return false,
+        };
+
+        let url = webview.uri().map(Into::into).unwrap_or_default();
+        if media_capture_permission_handler(url, kind) {
+          request.allow();
+        } else {
+          request.deny();
+        }
+        true
+      });
+    }
+
+    // Favicon changed handler
+    if let Some(favicon_changed_handler) = attributes.favicon_changed_handler.take() {
+      // The favicon database is off until its directory is set, which can only be done once per context
+      let context = web_context.context();
+      if context.favicon_database_directory().is_none() {
+        context.set_favicon_database_directory(None);
+      }
+      webview.connect_favicon_notify(move |webview| {
+        favicon_changed_handler(
+          webview
+            .favicon()
+            .and_then(|favicon| icon_from_surface(&favicon)),
+        );
+      });
+    }
+
+    // Fullscreen changed handler
+    if let Some(fullscreen_changed_handler) = attributes.fullscreen_changed_handler.take() {
+      let fullscreen_changed_handler = Rc::new(fullscreen_changed_handler);
+      let fullscreen_changed_handler_ = fullscreen_changed_handler.clone();
+      webview.connect_enter_fullscreen(move |_| {
+        fullscreen_changed_handler_(true);
+        false
+      });
+      webview.connect_leave_fullscreen(move |_| {
+        fullscreen_changed_handler(false);
+        false
+      });
+    }
+
     // Page load handler
     if let Some(on_page_load_handler) = attributes.on_page_load_handler.take() {
       webview.connect_load_changed(move |webview, load_event| match load_event {
@@ -429,6 +612,94 @@ impl InnerWebView {
       });
     }
 
+    // the initial html and the error page are loaded by wry, not the page, so they don't go
+    // through the navigation handler
+    let loading_html = Rc::new(Cell::new(
+      attributes.url.is_none() && attributes.html.is_some(),
+    ));
+
+    // Navigation failed handler and error page
+    let navigation_failed_handler = attributes.navigation_failed_handler.take().map(Rc::new);
+    let error_page_handler = attributes.error_page_handler.take();
+    if let Some(navigation_failed_handler) = navigation_failed_handler.clone() {
+      // HTTP errors are loaded as regular pages, so check the status of the main resource
+      webview.connect_load_changed(move |webview, load_event| {
+        if !matches!(load_event, LoadEvent::Committed) {
+          return;
+        }
+        let status = webview
+          .main_resource()
+          .and_then(|resource| resource.response())
+          .map_or(0, |response| response.status_code());
+        if status >= 400 {
+          navigation_failed_handler(NavigationError {
+            url: webview.uri().unwrap_or_default().to_string(),
+            kind: NavigationErrorKind::HttpStatus(status as u16),
+          });
+        }
+      });
+    }
+    if navigation_failed_handler.is_some() || error_page_handler.is_some() {
+      let loading_html = loading_html.clone();
+      webview.connect_load_failed(move |webview, _, uri, error| {
+        use gtk::gio::{IOErrorEnum, ResolverError, TlsError};
+        use webkit2gtk::{NetworkError, PolicyError};
+
+        // The load was turned into a download or stopped by the navigation handler
+        if error.kind::<PolicyError>().is_some() {
+          return false;
+        }
+
+        let kind = if error.kind::<ResolverError>().is_some() {
+          NavigationErrorKind::HostNotFound
+        } else if error.kind::<TlsError>().is_some() {
+          NavigationErrorKind::Tls
+        } else if let Some(error) = error.kind::<NetworkError>() {
+          match error {
+            NetworkError::Cancelled => NavigationErrorKind::Cancelled,
+            NetworkError::Transport => NavigationErrorKind::ConnectionFailed,
+            _ => NavigationErrorKind::Other,
+          }
+        } else {
+          match error.kind::<IOErrorEnum>() {
+            Some(IOErrorEnum::HostNotFound) => NavigationErrorKind::HostNotFound,
+            Some(
+              IOErrorEnum::HostUnreachable
+              | IOErrorEnum::NetworkUnreachable
+              | IOErrorEnum::ConnectionRefused
+              | IOErrorEnum::BrokenPipe
+              | IOErrorEnum::NotConnected,
+            ) => NavigationErrorKind::ConnectionFailed,
+            Some(IOErrorEnum::TimedOut) => NavigationErrorKind::Timeout,
+            Some(IOErrorEnum::Cancelled) => NavigationErrorKind::Cancelled,
+            _ => NavigationErrorKind::Other,
+          }
+        };
+
+        let error = NavigationError {
+          url: uri.to_string(),
+          kind,
+        };
+
+        let html = error_page_handler
+          .as_ref()
+          .filter(|_| error.has_error_page())
+          .and_then(|error_page_handler| error_page_handler(&error));
+        if let Some(html) = &html {
+          // without a base URI the page is `about:blank`, with an opaque origin
+          loading_html.set(true);
+          webview.load_html(html, None);
+        }
+
+        if let Some(navigation_failed_handler) = &navigation_failed_handler {
+          navigation_failed_handler(error);
+        }
+
+        // Returning `true` stops the default error page from being loaded
+        html.is_some()
+      });
+    }
+
     // Navigation handler && New window handler
     if attributes.navigation_handler.is_some() || attributes.new_window_req_handler.is_some() {
       let new_window_req_handler = attributes.new_window_req_handler.take();
@@ -446,6 +717,14 @@ impl InnerWebView {
             if let Some(nav_action) = policy.navigation_action() {
               if let Some(uri_req) = nav_action.request() {
                 if let Some(uri) = uri_req.uri() {
+                  // `load_html` without a base URI loads the html from `about:blank`
+                  if policy_type == PolicyDecisionType::NavigationAction
+                    && loading_html.take()
+                    && uri == "about:blank"
+                  {
+                    return false;
+                  }
+
                   let allow = handler(uri.to_string());
                   let pointer = policy_decision.as_ptr();
                   unsafe {
@@ -470,15 +749,22 @@ impl InnerWebView {
     // Download handler
     if attributes.download_started_handler.is_some()
       || attributes.download_completed_handler.is_some()
+      || attributes.download_progress_handler.is_some()
     {
       web_context.register_download_handler(
         attributes.download_started_handler.take(),
         attributes.download_completed_handler.take(),
+        attributes.download_progress_handler.take(),
       )
     }
   }
 
-  fn add_to_container<W>(webview: &WebView, container: &W, attributes: &WebViewAttributes) -> bool
+  fn add_to_container<W>(
+    webview: &WebView,
+    container: &W,
+    attributes: &WebViewAttributes,
+    pl_attrs: &super::PlatformSpecificWebViewAttributes,
+  ) -> bool
   where
     W: IsA<gtk::Container>,
   {
@@ -489,7 +775,12 @@ impl InnerWebView {
       container
         .dynamic_cast_ref::<gtk::Box>()
         .unwrap()
-        .pack_start(webview, true, true, 0);
+        .pack_start(
+          webview,
+          pl_attrs.box_expand,
+          pl_attrs.box_fill,
+          pl_attrs.box_padding,
+        );
     } else if container_type == "GtkFixed" {
       let scale_factor = webview.scale_factor() as f64;
       let (width, height) = attributes
@@ -573,6 +864,86 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn edit(&self, command: EditCommand) -> Result<()> {
+    self.webview.execute_editing_command(match command {
+      EditCommand::Copy => "Copy",
+      EditCommand::Cut => "Cut",
+      EditCommand::Paste => "Paste",
+      EditCommand::SelectAll => "SelectAll",
+    });
+    Ok(())
+  }
+
+  pub fn save_page<F>(&self, path: &Path, format: SaveFormat, handler: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + 'static,
+  {
+    match format {
+      SaveFormat::Mhtml => {
+        self.webview.save_to_file(
+          &gtk::gio::File::for_path(path),
+          webkit2gtk::SaveMode::Mhtml,
+          None::<&gtk::gio::Cancellable>,
+          move |result| handler(result.map_err(Into::into)),
+        );
+        Ok(())
+      }
+      _ => Err(Error::UnsupportedSaveFormat),
+    }
+  }
+
+  pub fn back_forward_list(&self) -> Result<BackForwardList> {
+    let Some(list) = self.webview.back_forward_list() else {
+      return Ok(BackForwardList::default());
+    };
+
+    let history_item = |item: webkit2gtk::BackForwardListItem| HistoryItem {
+      url: item.uri().map(Into::into).unwrap_or_default(),
+      title: item.title().map(Into::into).unwrap_or_default(),
+    };
+    // `nth_item` counts from the current item, back items are negative
+    let back = list.back_list().len() as i32;
+    let forward = list.forward_list().len() as i32;
+    Ok(BackForwardList {
+      back: (1..=back)
+        .rev()
+        .filter_map(|i| list.nth_item(-i))
+        .map(history_item)
+        .collect(),
+      current: list.current_item().map(history_item),
+      forward: (1..=forward)
+        .filter_map(|i| list.nth_item(i))
+        .map(history_item)
+        .collect(),
+    })
+  }
+
+  pub fn serialize_session(&self) -> Result<Vec<u8>> {
+    Ok(
+      self
+        .webview
+        .session_state()
+        .and_then(|state| state.serialize())
+        .map(|bytes| bytes.to_vec())
+        .unwrap_or_default(),
+    )
+  }
+
+  pub fn restore_session(&self, session: &[u8]) -> Result<()> {
+    if !session.is_empty() {
+      let state = webkit2gtk::WebViewSessionState::new(&glib::Bytes::from(session));
+      self.webview.restore_session_state(&state);
+      if let Some(item) = self
+        .webview
+        .back_forward_list()
+        .and_then(|list| list.current_item())
+      {
+        self.webview.go_to_back_forward_list_item(&item);
+      }
+    }
+    Ok(())
+  }
+
   pub fn url(&self) -> Result<String> {
     Ok(self.webview.uri().unwrap_or_default().to_string())
   }
@@ -684,11 +1055,28 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn load_url_with(&self, url: &str, cache_policy: CachePolicy) -> Result<()> {
+    match cache_policy.request_headers() {
+      Some(headers) => self.load_url_with_headers(url, headers),
+      None => self.load_url(url),
+    }
+  }
+
+  pub fn reload_bypassing_cache(&self) -> Result<()> {
+    self.webview.reload_bypass_cache();
+    Ok(())
+  }
+
   pub fn load_html(&self, html: &str) -> Result<()> {
     self.webview.load_html(html, None);
     Ok(())
   }
 
+  pub fn close(&self) -> Result<()> {
+    // the webview is torn down when dropped
+    Ok(())
+  }
+
   pub fn clear_all_browsing_data(&self) -> Result<()> {
     if let Some(context) = self.webview.context() {
       if let Some(data_manger) = context.website_data_manager() {
@@ -792,6 +1180,26 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn raise(&self) -> Result<()> {
+    if let Some(x11_data) = self.x11.as_ref().filter(|x| x.is_child) {
+      unsafe { (x11_data.xlib.XRaiseWindow)(x11_data.x11_display as _, x11_data.x11_window) };
+    } else if let Some(window) = self.webview.window() {
+      window.raise();
+    }
+
+    Ok(())
+  }
+
+  pub fn lower(&self) -> Result<()> {
+    if let Some(x11_data) = self.x11.as_ref().filter(|x| x.is_child) {
+      unsafe { (x11_data.xlib.XLowerWindow)(x11_data.x11_display as _, x11_data.x11_window) };
+    } else if let Some(window) = self.webview.window() {
+      window.lower();
+    }
+
+    Ok(())
+  }
+
   pub fn focus(&self) -> Result<()> {
     self.webview.grab_focus();
     Ok(())
@@ -902,6 +1310,17 @@ impl InnerWebView {
     }
   }
 
+  pub fn send_web_extension_message<F>(&self, message: &webkit2gtk::UserMessage, callback: F)
+  where
+    F: FnOnce(Result<webkit2gtk::UserMessage>) + 'static,
+  {
+    self
+      .webview
+      .send_message_to_page(message, None::<&Cancellable>, move |reply| {
+        callback(reply.map_err(Into::into))
+      });
+  }
+
   pub fn reparent<W>(&self, container: &W) -> Result<()>
   where
     W: gtk::prelude::IsA<gtk::Container>,
@@ -944,6 +1363,41 @@ pub fn platform_webview_version() -> Result<String> {
   Ok(format!("{major}.{minor}.{patch}"))
 }
 
+/// Move or maximize the toplevel window of `webview` like its title bar would.
+fn perform_drag_region_action(webview: &WebView, action: DragRegionAction) {
+  let Some(window) = webview
+    .toplevel()
+    .and_then(|w| w.downcast::<gtk::Window>().ok())
+  else {
+    return;
+  };
+
+  match action {
+    DragRegionAction::Drag => {
+      let Some(pointer) = window
+        .display()
+        .default_seat()
+        .and_then(|seat| seat.pointer())
+      else {
+        return;
+      };
+      let (_, x, y) = pointer.position();
+      window.begin_move_drag(1, x, y, gtk::current_event_time());
+    }
+    DragRegionAction::ToggleMaximize => {
+      if window.is_maximized() {
+        window.unmaximize();
+      } else {
+        window.maximize();
+      }
+    }
+  }
+}
+
+pub fn open_with_default_handler(uri: &str) {
+  let _ = gtk::gio::AppInfo::launch_default_for_uri(uri, None::<&gtk::gio::AppLaunchContext>);
+}
+
 // SAFETY: only use this when you are sure the span will be dropped on the same thread it was entered
 #[cfg(feature = "tracing")]
 struct SendEnteredSpan(tracing::span::EnteredSpan);
@@ -951,6 +1405,48 @@ struct SendEnteredSpan(tracing::span::EnteredSpan);
 #[cfg(feature = "tracing")]
 unsafe impl Send for SendEnteredSpan {}
 
+/// Copy a favicon surface, which is shared with WebKit and may not be an image surface,
+/// into non-premultiplied RGBA pixels.
+fn icon_from_surface(surface: &gtk::cairo::Surface) -> Option<Icon> {
+  use gtk::cairo::{Context, Format, ImageSurface};
+
+  let (width, height) = {
+    let image = ImageSurface::try_from(surface.clone()).ok()?;
+    (image.width(), image.height())
+  };
+
+  let mut copy = ImageSurface::create(Format::ARgb32, width, height).ok()?;
+  {
+    let cr = Context::new(&copy).ok()?;
+    cr.set_source_surface(surface, 0., 0.).ok()?;
+    cr.paint().ok()?;
+  }
+
+  let stride = copy.stride() as usize;
+  let data = copy.data().ok()?;
+  let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+  for row in data.chunks(stride).take(height as usize) {
+    for pixel in row[..width as usize * 4].chunks_exact(4) {
+      // ARGB32 is a native endian u32 with premultiplied alpha
+      let [b, g, r, a] = u32::from_ne_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]).to_le_bytes();
+      let unpremultiply = |c: u8| {
+        if a == 0 {
+          0
+        } else {
+          (c as u32 * 255 / a as u32) as u8
+        }
+      };
+      rgba.extend_from_slice(&[unpremultiply(r), unpremultiply(g), unpremultiply(b), a]);
+    }
+  }
+
+  Some(Icon {
+    rgba,
+    width: width as u32,
+    height: height as u32,
+  })
+}
+
 const BASE_DPI: f64 = 96.0;
 fn scale_factor_from_x11(xlib: &Xlib, display: *mut _XDisplay, parent: c_ulong) -> f64 {
   let mut attrs = unsafe { std::mem::zeroed() };
@@ -973,8 +1469,12 @@ mod ffi {
       translate::{FromGlibPtrContainer, ToGlibPtr},
     },
   };
-  use webkit2gtk::CookieManager;
-  use webkit2gtk_sys::WebKitCookieManager;
+  use webkit2gtk::{CookieManager, UserContentManager};
+  use webkit2gtk_sys::{
+    webkit_user_content_filter_store_new, webkit_user_content_filter_store_save,
+    webkit_user_content_filter_store_save_finish, webkit_user_content_filter_unref,
+    webkit_user_content_manager_add_filter, WebKitCookieManager,
+  };
 
   pub trait CookieManageExt: IsA<CookieManager> + 'static {
     fn all_cookies<P: FnOnce(std::result::Result<Vec<soup::Cookie>, glib::Error>) + 'static>(
@@ -1028,6 +1528,75 @@ mod ffi {
 
   impl CookieManageExt for CookieManager {}
 
+  /// A call to [`add_content_filters`] waiting for its current rule list to compile.
+  struct PendingContentFilters {
+    manager: UserContentManager,
+    storage_path: String,
+    filters: std::vec::IntoIter<(String, String)>,
+    ready: Box<dyn FnOnce()>,
+  }
+
+  /// Compile the content blocker rule lists into the store at `storage_path` and add them
+  /// to `manager` one after the other, then call `ready`, even if some failed to compile.
+  pub fn add_content_filters(
+    manager: &UserContentManager,
+    storage_path: String,
+    mut filters: std::vec::IntoIter<(String, String)>,
+    ready: Box<dyn FnOnce()>,
+  ) {
+    unsafe extern "C" fn save_trampoline(
+      source_object: *mut glib::gobject_ffi::GObject,
+      res: *mut gdk::gio::ffi::GAsyncResult,
+      user_data: glib::ffi::gpointer,
+    ) {
+      let pending: Box<PendingContentFilters> = Box::from_raw(user_data as *mut _);
+      let mut error = std::ptr::null_mut();
+      let filter =
+        webkit_user_content_filter_store_save_finish(source_object as *mut _, res, &mut error);
+      if error.is_null() {
+        webkit_user_content_manager_add_filter(pending.manager.to_glib_none().0, filter);
+        webkit_user_content_filter_unref(filter);
+      } else {
+        let _error: glib::Error = glib::translate::from_glib_full(error);
+        #[cfg(feature = "tracing")]
+        tracing::warn!("Failed to compile the content filter: {_error}");
+      }
+
+      let PendingContentFilters {
+        manager,
+        storage_path,
+        filters,
+        ready,
+      } = *pending;
+      add_content_filters(&manager, storage_path, filters, ready);
+    }
+
+    let Some((identifier, rules)) = filters.next() else {
+      return ready();
+    };
+
+    let rules = glib::Bytes::from(rules.as_bytes());
+    unsafe {
+      let store = webkit_user_content_filter_store_new(storage_path.to_glib_none().0);
+      let user_data = Box::new(PendingContentFilters {
+        manager: manager.clone(),
+        storage_path,
+        filters,
+        ready,
+      });
+      webkit_user_content_filter_store_save(
+        store,
+        identifier.to_glib_none().0,
+        rules.to_glib_none().0,
+        std::ptr::null_mut(),
+        Some(save_trampoline),
+        Box::into_raw(user_data) as *mut _,
+      );
+      // the pending operation holds its own reference to the store
+      glib::gobject_ffi::g_object_unref(store as *mut _);
+    }
+  }
+
   extern "C" {
     pub fn webkit_cookie_manager_get_all_cookies(
       cookie_manager: *mut webkit2gtk_sys::WebKitCookieManager,
@@ -21,9 +21,9 @@ use std::{
 };
 use webkit2gtk::{
   ApplicationInfo, AutomationSessionExt, CookiePersistentStorage, DownloadExt, LoadEvent,
-  SecurityManagerExt, URIRequest, URIRequestExt, URISchemeRequest, URISchemeRequestExt,
-  URISchemeResponse, URISchemeResponseExt, WebContext, WebContextExt as Webkit2gtkContextExt,
-  WebView, WebViewExt,
+  SecurityManagerExt, URIRequest, URIRequestExt, URIResponseExt, URISchemeRequest,
+  URISchemeRequestExt, URISchemeResponse, URISchemeResponseExt, WebContext,
+  WebContextExt as Webkit2gtkContextExt, WebView, WebViewExt,
 };
 
 #[derive(Debug)]
@@ -32,6 +32,10 @@ pub struct WebContextImpl {
   webview_uri_loader: Rc<WebViewUriLoader>,
   automation: bool,
   app_info: Option<ApplicationInfo>,
+  web_extensions_directory: Option<PathBuf>,
+  /// A live webview of the context whose web process the next webviews share,
+  /// with [`ProcessModel::Shared`](crate::ProcessModel::Shared).
+  shared_process_view: glib::WeakRef<WebView>,
 }
 
 impl WebContextImpl {
@@ -85,6 +89,8 @@ impl WebContextImpl {
       automation,
       webview_uri_loader: Rc::default(),
       app_info: Some(app_info),
+      web_extensions_directory: None,
+      shared_process_view: Default::default(),
     }
   }
 
@@ -92,6 +98,42 @@ impl WebContextImpl {
     self.automation = flag;
     self.context.set_automation_allowed(flag);
   }
+
+  pub fn set_web_extensions_directory(&mut self, directory: &Path) {
+    self
+      .context
+      .set_web_extensions_directory(&directory.to_string_lossy());
+    self.web_extensions_directory = Some(directory.to_path_buf());
+  }
+
+  pub fn shared_process_view(&self) -> Option<WebView> {
+    self.shared_process_view.upgrade()
+  }
+
+  pub fn set_shared_process_view(&mut self, webview: &WebView) {
+    self.shared_process_view.set(Some(webview));
+  }
+
+  /// Like [`WebContextExt::queue_load_uri`] followed by [`WebContextExt::flush_queue_loader`],
+  /// for loads that start after the webview is created, e.g. once its content filters are ready.
+  pub fn deferred_load_uri(&self) -> impl FnOnce(WebView, String, Option<http::HeaderMap>) {
+    let loader = self.webview_uri_loader.clone();
+    move |webview, uri, headers| {
+      loader.push(webview, uri, headers);
+      loader.flush();
+    }
+  }
+
+  /// Recreate the context on top of a freshly wiped data directory.
+  pub fn reset(&mut self, data_directory: &Path) {
+    let automation = self.automation;
+    let web_extensions_directory = self.web_extensions_directory.take();
+    *self = Self::new(Some(data_directory));
+    self.set_allows_automation(automation);
+    if let Some(directory) = web_extensions_directory {
+      self.set_web_extensions_directory(&directory);
+    }
+  }
 }
 
 /// [`WebContext`](super::WebContext) items that only matter on unix.
@@ -125,6 +167,7 @@ pub trait WebContextExt {
     &mut self,
     download_started_callback: Option<Box<dyn FnMut(String, &mut PathBuf) -> bool>>,
     download_completed_callback: Option<Rc<dyn Fn(String, Option<PathBuf>, bool) + 'static>>,
+    download_progress_callback: Option<Rc<dyn Fn(String, u64, Option<u64>) + 'static>>,
   );
 }
 
@@ -223,21 +266,27 @@ impl WebContextExt for super::WebContext {
         let responder: Box<dyn FnOnce(HttpResponse<Cow<'static, [u8]>>)> =
           Box::new(move |http_response| {
             MainContext::default().invoke(move || {
-              let buffer = http_response.body();
-              let input = gtk::gio::MemoryInputStream::from_bytes(&gtk::glib::Bytes::from(buffer));
-              let content_type = http_response
-                .headers()
+              let (parts, body) = http_response.into_parts();
+              // hand the body to glib without copying it, static data is borrowed as is
+              // and owned buffers are moved into the `Bytes`.
+              let buffer = match body {
+                Cow::Borrowed(b) => gtk::glib::Bytes::from_static(b),
+                Cow::Owned(b) => gtk::glib::Bytes::from_owned(b),
+              };
+              let input = gtk::gio::MemoryInputStream::from_bytes(&buffer);
+              let content_type = parts
+                .headers
                 .get(CONTENT_TYPE)
                 .and_then(|h| h.to_str().ok());
 
               let response = URISchemeResponse::new(&input, buffer.len() as i64);
-              response.set_status(http_response.status().as_u16() as u32, None);
+              response.set_status(parts.status.as_u16() as u32, None);
               if let Some(content_type) = content_type {
                 response.set_content_type(content_type);
               }
 
               let headers = MessageHeaders::new(MessageHeadersType::Response);
-              for (name, value) in http_response.headers().into_iter() {
+              for (name, value) in parts.headers.iter() {
                 headers.append(name.as_str(), value.to_str().unwrap_or(""));
               }
               response.set_http_headers(headers);
@@ -301,6 +350,7 @@ impl WebContextExt for super::WebContext {
     &mut self,
     download_started_handler: Option<Box<dyn FnMut(String, &mut PathBuf) -> bool>>,
     download_completed_handler: Option<Rc<dyn Fn(String, Option<PathBuf>, bool) + 'static>>,
+    download_progress_handler: Option<Rc<dyn Fn(String, u64, Option<u64>) + 'static>>,
   ) {
     let context = &self.os.context;
 
@@ -333,6 +383,19 @@ impl WebContextExt for super::WebContext {
         }
       });
 
+      if let Some(download_progress_handler) = download_progress_handler.clone() {
+        download.connect_received_data(move |download, _data_length| {
+          if let Some(uri) = download.request().and_then(|req| req.uri()) {
+            // the content length is 0 when the server didn't send one
+            let total = download
+              .response()
+              .map(|response| response.content_length())
+              .filter(|&length| length > 0);
+            download_progress_handler(uri.to_string(), download.received_data_length(), total);
+          }
+        });
+      }
+
       if let Some(download_completed_handler) = download_completed_handler.clone() {
         download.connect_finished({
           let failed = failed.clone();
@@ -6,7 +6,13 @@ mod drag_drop;
 mod util;
 
 use std::{
-  borrow::Cow, cell::RefCell, collections::HashSet, fmt::Write, path::PathBuf, rc::Rc, sync::mpsc,
+  borrow::Cow,
+  cell::{Cell, RefCell},
+  collections::HashSet,
+  fmt::Write,
+  path::{Path, PathBuf},
+  rc::Rc,
+  sync::mpsc,
 };
 
 use dpi::{PhysicalPosition, PhysicalSize};
@@ -19,19 +25,41 @@ use windows::{
   Win32::{
     Foundation::*,
     Globalization::*,
-    Graphics::Gdi::*,
-    System::{Com::*, LibraryLoader::GetModuleHandleW, WinRT::EventRegistrationToken},
-    UI::{Input::KeyboardAndMouse::SetFocus, Shell::*, WindowsAndMessaging::*},
+    Graphics::{Gdi::*, Imaging::*},
+    System::{
+      Com::*,
+      LibraryLoader::GetModuleHandleW,
+      ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS},
+      Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION},
+      WinRT::EventRegistrationToken,
+    },
+    UI::{
+      Input::KeyboardAndMouse::{
+        GetKeyState, ReleaseCapture, SetFocus, VIRTUAL_KEY, VK_CONTROL, VK_LWIN, VK_RWIN, VK_SHIFT,
+      },
+      Shell::*,
+      WindowsAndMessaging::*,
+    },
   },
 };
 
 use self::drag_drop::DragDropController;
 use super::Theme;
 use crate::{
-  proxy::ProxyConfig, Error, MemoryUsageLevel, PageLoadEvent, Rect, RequestAsyncResponder, Result,
-  WebViewAttributes, RGBA,
+  drag_region::DragRegionAction, proxy::ProxyConfig, BackForwardList, CachePolicy, EditCommand,
+  Error, Icon, KeyEvent, MediaCaptureKind, MemoryUsageLevel, NavigationError, NavigationErrorKind,
+  PageLoadEvent, ProcessModel, Rect, RequestAsyncResponder, Result, SaveFormat, WebViewAttributes,
+  RGBA,
 };
 
+const FULLSCREEN_CONTENT_DISALLOWED_SCRIPT: &str = r#"(function () {
+  const reject = () => Promise.reject(new TypeError('Fullscreen is not allowed'));
+  Element.prototype.requestFullscreen = reject;
+  Element.prototype.webkitRequestFullscreen = reject;
+  Object.defineProperty(Document.prototype, 'fullscreenEnabled', { get: () => false });
+  Object.defineProperty(Document.prototype, 'webkitFullscreenEnabled', { get: () => false });
+})();"#;
+
 const PARENT_SUBCLASS_ID: u32 = WM_USER + 0x64;
 const PARENT_DESTROY_MESSAGE: u32 = WM_USER + 0x65;
 const MAIN_THREAD_DISPATCHER_SUBCLASS_ID: u32 = WM_USER + 0x66;
@@ -55,8 +83,8 @@ pub(crate) struct InnerWebView {
   hwnd: HWND,
   is_child: bool,
   pub controller: ICoreWebView2Controller,
-  webview: ICoreWebView2,
-  env: ICoreWebView2Environment,
+  pub webview: ICoreWebView2,
+  pub env: ICoreWebView2Environment,
   // Store FileDropController in here to make sure it gets dropped when
   // the webview gets dropped, otherwise we'll have a memory leak
   #[allow(dead_code)]
@@ -65,7 +93,7 @@ pub(crate) struct InnerWebView {
 
 impl Drop for InnerWebView {
   fn drop(&mut self) {
-    let _ = unsafe { self.controller.Close() };
+    let _ = self.close();
     if self.is_child {
       let _ = unsafe { DestroyWindow(self.hwnd) };
     }
@@ -262,7 +290,7 @@ impl InnerWebView {
       .map(HSTRING::from);
 
     // additional browser args
-    let additional_browser_args = pl_attrs.additional_browser_args.unwrap_or_else(|| {
+    let mut additional_browser_args = pl_attrs.additional_browser_args.unwrap_or_else(|| {
       // remove "mini menu" - See https://github.com/tauri-apps/wry/issues/535
       // and "smart screen" - See https://github.com/tauri-apps/tauri/issues/1345
       let default_args = "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
@@ -292,17 +320,50 @@ impl InnerWebView {
       arguments
     });
 
+    if let Some(context) = attributes.context.as_deref() {
+      if !context.host_resolver_rules.is_empty() {
+        let rules = context
+          .host_resolver_rules
+          .iter()
+          .map(|(host, address)| format!("MAP {host} {address}"))
+          .collect::<Vec<_>>()
+          .join(",");
+        let _ = write!(
+          additional_browser_args,
+          " --host-resolver-rules=\"{rules}\""
+        );
+      }
+
+      if context.process_model() == ProcessModel::Shared {
+        additional_browser_args.push_str(" --renderer-process-limit=1");
+      }
+    }
+
+    let browser_executable_folder = pl_attrs
+      .browser_executable_folder
+      .as_deref()
+      .map(HSTRING::from);
+
     let (tx, rx) = mpsc::channel();
     let options = CoreWebView2EnvironmentOptions::default();
     unsafe {
       options.set_additional_browser_arguments(additional_browser_args);
       options.set_are_browser_extensions_enabled(pl_attrs.browser_extensions_enabled);
 
-      // Get user's system language
-      let lcid = GetUserDefaultUILanguage();
-      let mut lang = [0; MAX_LOCALE_NAME as usize];
-      LCIDToLocaleName(lcid as u32, Some(&mut lang), LOCALE_ALLOW_NEUTRAL_NAMES);
-      options.set_language(String::from_utf16_lossy(&lang));
+      let language = pl_attrs.language.unwrap_or_else(|| {
+        // Get user's system language
+        let lcid = GetUserDefaultUILanguage();
+        let mut lang = [0; MAX_LOCALE_NAME as usize];
+        LCIDToLocaleName(lcid as u32, Some(&mut lang), LOCALE_ALLOW_NEUTRAL_NAMES);
+        String::from_utf16_lossy(&lang)
+      });
+      options.set_language(language);
+
+      let channel_search_kind = match pl_attrs.release_channel_preference {
+        ReleaseChannelPreference::MostStable => COREWEBVIEW2_CHANNEL_SEARCH_KIND_MOST_STABLE,
+        ReleaseChannelPreference::LeastStable => COREWEBVIEW2_CHANNEL_SEARCH_KIND_LEAST_STABLE,
+      };
+      options.set_channel_search_kind(channel_search_kind);
 
       let scroll_bar_style = match pl_attrs.scroll_bar_style {
         ScrollBarStyle::Default => COREWEBVIEW2_SCROLLBAR_STYLE_DEFAULT,
@@ -312,7 +373,10 @@ impl InnerWebView {
       options.set_scroll_bar_style(scroll_bar_style);
 
       CreateCoreWebView2EnvironmentWithOptions(
-        PCWSTR::null(),
+        browser_executable_folder
+          .as_ref()
+          .map(|f| PCWSTR::from_raw(f.as_ptr()))
+          .unwrap_or_else(PCWSTR::null),
         &data_directory.unwrap_or_default(),
         &ICoreWebView2EnvironmentOptions::from(options),
         // we don't use CreateCoreWebView2EnvironmentCompletedHandler::wait_for_async
@@ -415,6 +479,90 @@ impl InnerWebView {
     // Webview handlers
     unsafe { Self::attach_handlers(hwnd, &webview, &mut attributes, &mut token)? };
 
+    // Accelerator keys handler
+    if let Some(handler) = pl_attrs.accelerator_key_handler.clone() {
+      unsafe { Self::attach_accelerator_key_handler(controller, handler, &mut token)? };
+    }
+
+    // Focus changed handler
+    if let Some(focus_changed_handler) = attributes.focus_changed_handler.take() {
+      let focus_changed_handler = Rc::new(focus_changed_handler);
+      let focus_changed_handler_ = focus_changed_handler.clone();
+      unsafe {
+        controller.add_GotFocus(
+          &FocusChangedEventHandler::create(Box::new(move |_, _| {
+            focus_changed_handler_(true);
+            Ok(())
+          })),
+          &mut token,
+        )?;
+        controller.add_LostFocus(
+          &FocusChangedEventHandler::create(Box::new(move |_, _| {
+            focus_changed_handler(false);
+            Ok(())
+          })),
+          &mut token,
+        )?;
+      }
+    }
+
+    // Move focus handler, hands Tab/Shift+Tab out of the web content over to the next
+    // native tab stop so keyboard users are not trapped inside the webview
+    unsafe {
+      controller.add_MoveFocusRequested(
+        &MoveFocusRequestedEventHandler::create(Box::new(move |_, args| {
+          let Some(args) = args else {
+            return Ok(());
+          };
+
+          let mut reason = COREWEBVIEW2_MOVE_FOCUS_REASON::default();
+          args.Reason(&mut reason)?;
+          let previous = match reason {
+            COREWEBVIEW2_MOVE_FOCUS_REASON_NEXT => false,
+            COREWEBVIEW2_MOVE_FOCUS_REASON_PREVIOUS => true,
+            _ => return Ok(()),
+          };
+
+          let root = GetAncestor(hwnd, GA_ROOT);
+          if let Ok(next) = GetNextDlgTabItem(root, hwnd, previous) {
+            if next != hwnd && !IsChild(hwnd, next).as_bool() {
+              SetFocus(next)?;
+              args.SetHandled(true)?;
+            }
+          }
+
+          Ok(())
+        })),
+        &mut token,
+      )?;
+    }
+
+    // Key event handler, WebView2 only reports accelerator keys to the host
+    if let Some(key_event_handler) = attributes.key_event_handler.take() {
+      let handler = Rc::new(move |event: AcceleratorKeyEvent| {
+        let is_key_down = |vk: VIRTUAL_KEY| unsafe { GetKeyState(vk.0 as i32) } < 0;
+        key_event_handler(KeyEvent {
+          native_key_code: event.virtual_key,
+          pressed: event.pressed,
+          repeat: event.repeat,
+          ctrl: event.ctrl,
+          shift: event.shift,
+          alt: event.alt,
+          meta: is_key_down(VK_LWIN) || is_key_down(VK_RWIN),
+        })
+      });
+      unsafe { Self::attach_accelerator_key_handler(controller, handler, &mut token)? };
+    }
+
+    // Drag regions, only reported by the runtimes without non-client region support
+    if attributes.drag_regions {
+      let _ = pl_attrs
+        .drag_region_target
+        .set(Box::new(move |action| unsafe {
+          perform_drag_region_action(hwnd, action)
+        }));
+    }
+
     // IPC handler
     unsafe { Self::attach_ipc_handler(&webview, &mut attributes, &mut token)? };
 
@@ -439,22 +587,61 @@ impl InnerWebView {
       };
     }
 
+    // Fullscreen content, WebView2 has no setting to turn it off so reject the requests from JS
+    if !attributes.fullscreen_content_allowed {
+      Self::add_script_to_execute_on_document_created(
+        &webview,
+        FULLSCREEN_CONTENT_DISALLOWED_SCRIPT.to_string(),
+      )?;
+    }
+
     // Initialize scripts
     for js in attributes.initialization_scripts {
       Self::add_script_to_execute_on_document_created(&webview, js)?;
     }
 
-    // Enable clipboard
-    if attributes.clipboard {
+    // Enable clipboard, or let the clipboard and media capture permission handlers decide
+    let clipboard = attributes.clipboard;
+    let clipboard_permission_handler = attributes.clipboard_permission_handler.take();
+    let media_capture_permission_handler = attributes.media_capture_permission_handler.take();
+    if clipboard
+      || clipboard_permission_handler.is_some()
+      || media_capture_permission_handler.is_some()
+    {
       unsafe {
         webview.add_PermissionRequested(
-          &PermissionRequestedEventHandler::create(Box::new(|_, args| {
+          &PermissionRequestedEventHandler::create(Box::new(move |_, args| {
             let Some(args) = args else { return Ok(()) };
 
             let mut kind = COREWEBVIEW2_PERMISSION_KIND::default();
             args.PermissionKind(&mut kind)?;
-            if kind == COREWEBVIEW2_PERMISSION_KIND_CLIPBOARD_READ {
-              args.SetState(COREWEBVIEW2_PERMISSION_STATE_ALLOW)?;
+            let uri = || {
+              let mut uri = PWSTR::null();
+              args.Uri(&mut uri).map(|_| take_pwstr(uri))
+            };
+
+            let allow = match (kind, &media_capture_permission_handler) {
+              (COREWEBVIEW2_PERMISSION_KIND_CLIPBOARD_READ, _) => {
+                match &clipboard_permission_handler {
+                  Some(handler) => Some(handler(uri()?)),
+                  None => clipboard.then_some(true),
+                }
+              }
+              (COREWEBVIEW2_PERMISSION_KIND_CAMERA, Some(handler)) => {
+                Some(handler(uri()?, MediaCaptureKind::Camera))
+              }
+              (COREWEBVIEW2_PERMISSION_KIND_MICROPHONE, Some(handler)) => {
+                Some(handler(uri()?, MediaCaptureKind::Microphone))
+              }
+              _ => None,
+            };
+
+            if let Some(allow) = allow {
+              args.SetState(if allow {
+                COREWEBVIEW2_PERMISSION_STATE_ALLOW
+              } else {
+                COREWEBVIEW2_PERMISSION_STATE_DENY
+              })?;
             }
 
             Ok(())
@@ -464,6 +651,11 @@ impl InnerWebView {
       }
     }
 
+    // Virtual hosts, mapped before navigating so the initial url can use them
+    for (host, folder, access) in &pl_attrs.virtual_host_mappings {
+      unsafe { set_virtual_host_name_to_folder_mapping(&webview, host, folder, *access)? };
+    }
+
     // Navigation
     if let Some(mut url) = attributes.url {
       if let Some(pos) = url.find("://") {
@@ -513,6 +705,7 @@ impl InnerWebView {
     settings.SetAreDefaultContextMenusEnabled(true)?;
     settings.SetIsZoomControlEnabled(attributes.zoom_hotkeys_enabled)?;
     settings.SetAreDevToolsEnabled(attributes.devtools)?;
+    settings.SetIsScriptEnabled(attributes.javascript_enabled)?;
 
     if let Some(user_agent) = &attributes.user_agent {
       if let Ok(settings2) = settings.cast::<ICoreWebView2Settings2>() {
@@ -541,6 +734,48 @@ impl InnerWebView {
     Ok(())
   }
 
+  #[inline]
+  unsafe fn attach_accelerator_key_handler(
+    controller: &ICoreWebView2Controller,
+    handler: Rc<dyn Fn(AcceleratorKeyEvent) -> bool>,
+    token: &mut EventRegistrationToken,
+  ) -> Result<()> {
+    controller.add_AcceleratorKeyPressed(
+      &AcceleratorKeyPressedEventHandler::create(Box::new(move |_, args| {
+        let Some(args) = args else {
+          return Ok(());
+        };
+
+        let mut kind = COREWEBVIEW2_KEY_EVENT_KIND::default();
+        args.KeyEventKind(&mut kind)?;
+        let mut virtual_key = 0;
+        args.VirtualKey(&mut virtual_key)?;
+        let mut status = COREWEBVIEW2_PHYSICAL_KEY_STATUS::default();
+        args.PhysicalKeyStatus(&mut status)?;
+
+        let is_key_down = |vk: VIRTUAL_KEY| GetKeyState(vk.0 as i32) < 0;
+        let event = AcceleratorKeyEvent {
+          virtual_key,
+          pressed: kind == COREWEBVIEW2_KEY_EVENT_KIND_KEY_DOWN
+            || kind == COREWEBVIEW2_KEY_EVENT_KIND_SYSTEM_KEY_DOWN,
+          repeat: status.WasKeyDown.as_bool(),
+          ctrl: is_key_down(VK_CONTROL),
+          shift: is_key_down(VK_SHIFT),
+          alt: status.IsMenuKeyDown.as_bool(),
+        };
+
+        if handler(event) {
+          args.SetHandled(true)?;
+        }
+
+        Ok(())
+      })),
+      token,
+    )?;
+
+    Ok(())
+  }
+
   #[inline]
   unsafe fn attach_handlers(
     hwnd: HWND,
@@ -575,6 +810,51 @@ impl InnerWebView {
       )?;
     }
 
+    // Favicon changed handler
+    if let Some(favicon_changed_handler) = attributes.favicon_changed_handler.take() {
+      if let Ok(webview) = webview.cast::<ICoreWebView2_15>() {
+        let favicon_changed_handler = Rc::new(favicon_changed_handler);
+        webview.add_FaviconChanged(
+          &FaviconChangedEventHandler::create(Box::new(move |webview, _| {
+            let Some(webview) = webview.and_then(|w| w.cast::<ICoreWebView2_15>().ok()) else {
+              return Ok(());
+            };
+
+            let favicon_changed_handler = favicon_changed_handler.clone();
+            webview.GetFavicon(
+              COREWEBVIEW2_FAVICON_IMAGE_FORMAT_PNG,
+              &GetFaviconCompletedHandler::create(Box::new(move |result, stream| {
+                let icon = result
+                  .ok()
+                  .and(stream)
+                  .and_then(|stream| decode_icon(&stream).ok());
+                favicon_changed_handler(icon);
+                Ok(())
+              })),
+            )
+          })),
+          token,
+        )?;
+      }
+    }
+
+    // Fullscreen changed handler
+    if let Some(fullscreen_changed_handler) = attributes.fullscreen_changed_handler.take() {
+      webview.add_ContainsFullScreenElementChanged(
+        &ContainsFullScreenElementChangedEventHandler::create(Box::new(move |webview, _| {
+          let Some(webview) = webview else {
+            return Ok(());
+          };
+
+          let mut fullscreen = BOOL::default();
+          webview.ContainsFullScreenElement(&mut fullscreen)?;
+          fullscreen_changed_handler(fullscreen.as_bool());
+          Ok(())
+        })),
+        token,
+      )?;
+    }
+
     // Page load handler
     if let Some(on_page_load_handler) = attributes.on_page_load_handler.take() {
       let on_page_load_handler = Rc::new(on_page_load_handler);
@@ -605,6 +885,86 @@ impl InnerWebView {
       )?;
     }
 
+    // the initial html and the error page are loaded by wry, not the page, so they don't go
+    // through the navigation handler
+    let loading_html = Rc::new(Cell::new(
+      attributes.url.is_none() && attributes.html.is_some(),
+    ));
+
+    // Navigation failed handler and error page
+    let navigation_failed_handler = attributes.navigation_failed_handler.take();
+    let error_page_handler = attributes.error_page_handler.take();
+    if navigation_failed_handler.is_some() || error_page_handler.is_some() {
+      let loading_html = loading_html.clone();
+      webview.add_NavigationCompleted(
+        &NavigationCompletedEventHandler::create(Box::new(move |webview, args| {
+          let (Some(webview), Some(args)) = (webview, args) else {
+            return Ok(());
+          };
+
+          let mut is_success = BOOL::default();
+          args.IsSuccess(&mut is_success)?;
+          if is_success.as_bool() {
+            return Ok(());
+          }
+
+          let mut http_status = 0;
+          if let Ok(args) = args.cast::<ICoreWebView2NavigationCompletedEventArgs2>() {
+            args.HttpStatusCode(&mut http_status)?;
+          }
+
+          let kind = if http_status >= 400 {
+            NavigationErrorKind::HttpStatus(http_status as u16)
+          } else {
+            let mut status = COREWEBVIEW2_WEB_ERROR_STATUS::default();
+            args.WebErrorStatus(&mut status)?;
+            match status {
+              COREWEBVIEW2_WEB_ERROR_STATUS_HOST_NAME_NOT_RESOLVED => {
+                NavigationErrorKind::HostNotFound
+              }
+              COREWEBVIEW2_WEB_ERROR_STATUS_SERVER_UNREACHABLE
+              | COREWEBVIEW2_WEB_ERROR_STATUS_CONNECTION_ABORTED
+              | COREWEBVIEW2_WEB_ERROR_STATUS_CONNECTION_RESET
+              | COREWEBVIEW2_WEB_ERROR_STATUS_DISCONNECTED
+              | COREWEBVIEW2_WEB_ERROR_STATUS_CANNOT_CONNECT => {
+                NavigationErrorKind::ConnectionFailed
+              }
+              COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_COMMON_NAME_IS_INCORRECT
+              | COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_EXPIRED
+              | COREWEBVIEW2_WEB_ERROR_STATUS_CLIENT_CERTIFICATE_CONTAINS_ERRORS
+              | COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_REVOKED
+              | COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_IS_INVALID => NavigationErrorKind::Tls,
+              COREWEBVIEW2_WEB_ERROR_STATUS_TIMEOUT => NavigationErrorKind::Timeout,
+              COREWEBVIEW2_WEB_ERROR_STATUS_OPERATION_CANCELED => NavigationErrorKind::Cancelled,
+              _ => NavigationErrorKind::Other,
+            }
+          };
+
+          let error = NavigationError {
+            url: Self::url_from_webview(&webview)?,
+            kind,
+          };
+
+          let html = error_page_handler
+            .as_ref()
+            .filter(|_| error.has_error_page())
+            .and_then(|error_page_handler| error_page_handler(&error));
+          let mut result = Ok(());
+          if let Some(html) = html {
+            result = webview.NavigateToString(&HSTRING::from(html));
+            loading_html.set(result.is_ok());
+          }
+
+          if let Some(navigation_failed_handler) = &navigation_failed_handler {
+            navigation_failed_handler(error);
+          }
+
+          result
+        })),
+        token,
+      )?;
+    }
+
     // Navigation handler
     if let Some(nav_callback) = attributes.navigation_handler.take() {
       webview.add_NavigationStarting(
@@ -619,6 +979,11 @@ impl InnerWebView {
             take_pwstr(uri)
           };
 
+          // `NavigateToString` loads the html from a `data:` URL
+          if loading_html.take() && uri.starts_with("data:") {
+            return Ok(());
+          }
+
           let allow = nav_callback(uri);
           args.SetCancel(!allow)?;
 
@@ -654,9 +1019,11 @@ impl InnerWebView {
     // Download handler
     if attributes.download_started_handler.is_some()
       || attributes.download_completed_handler.is_some()
+      || attributes.download_progress_handler.is_some()
     {
       let mut download_started_handler = attributes.download_started_handler.take();
       let download_completed_handler = attributes.download_completed_handler.take();
+      let download_progress_handler = attributes.download_progress_handler.take();
 
       let webview4: ICoreWebView2_4 = webview.cast()?;
       webview4.add_DownloadStarting(
@@ -671,6 +1038,35 @@ impl InnerWebView {
             take_pwstr(uri)
           };
 
+          if let Some(download_progress_handler) = &download_progress_handler {
+            let download_progress_handler = download_progress_handler.clone();
+
+            args.DownloadOperation()?.add_BytesReceivedChanged(
+              &BytesReceivedChangedEventHandler::create(Box::new(move |download_operation, _| {
+                let Some(download_operation) = download_operation else {
+                  return Ok(());
+                };
+
+                let uri = {
+                  let mut uri = PWSTR::null();
+                  download_operation.Uri(&mut uri)?;
+                  take_pwstr(uri)
+                };
+
+                let mut received = 0;
+                download_operation.BytesReceived(&mut received)?;
+                // the total is -1 when the server didn't send a content length
+                let mut total = 0;
+                download_operation.TotalBytesToReceive(&mut total)?;
+
+                download_progress_handler(uri, received.max(0) as u64, u64::try_from(total).ok());
+
+                Ok(())
+              })),
+              &mut EventRegistrationToken::default(),
+            )?;
+          }
+
           if let Some(download_completed_handler) = &download_completed_handler {
             let download_completed_handler = download_completed_handler.clone();
 
@@ -744,7 +1140,8 @@ impl InnerWebView {
   ) -> Result<()> {
     Self::add_script_to_execute_on_document_created(
       webview,
-      String::from(
+      crate::ipc::init_script(
+        attributes.ipc_origins.as_deref(),
         r#"Object.defineProperty(window, 'ipc', { value: Object.freeze({ postMessage: s=> window.chrome.webview.postMessage(s) }) });"#,
       ),
     )?;
@@ -794,7 +1191,22 @@ impl InnerWebView {
       // WebView2 supports non-standard protocols only on Windows 10+, so we have to use this workaround
       // See https://github.com/MicrosoftEdge/WebView2Feedback/issues/73
       let filter = HSTRING::from(format!("{scheme}://{name}.*"));
-      webview.AddWebResourceRequestedFilter(&filter, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL)?;
+      // by default only the requests of the page are filtered, not the ones of its workers
+      let worker_webview = attributes
+        .secure_custom_protocols
+        .contains(name)
+        .then(|| webview.cast::<ICoreWebView2_22>().ok())
+        .flatten();
+      match worker_webview {
+        Some(webview) => webview.AddWebResourceRequestedFilterWithRequestSourceKinds(
+          &filter,
+          COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
+          COREWEBVIEW2_WEB_RESOURCE_REQUEST_SOURCE_KINDS_ALL,
+        )?,
+        None => {
+          webview.AddWebResourceRequestedFilter(&filter, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL)?
+        }
+      }
     }
 
     let env = env.clone();
@@ -1087,7 +1499,12 @@ impl InnerWebView {
       msg if msg == WM_DESTROY || msg == PARENT_DESTROY_MESSAGE => {
         // check if `dwrefdata` is null to avoid double-freeing the controller
         if !(dwrefdata as *mut ()).is_null() {
-          drop(Box::from_raw(dwrefdata as *mut ICoreWebView2Controller));
+          let controller = Box::from_raw(dwrefdata as *mut ICoreWebView2Controller);
+          // the parent is destroyed before the webview was dropped, close it while its windows still exist
+          if msg == WM_DESTROY {
+            let _ = controller.Close();
+          }
+          drop(controller);
 
           // update `dwrefdata` to null to avoid double-freeing the controller
           let _ = SetWindowSubclass(
@@ -1212,6 +1629,18 @@ impl InnerWebView {
     load_url_with_headers(&self.webview, &self.env, url, headers)
   }
 
+  pub fn load_url_with(&self, url: &str, cache_policy: CachePolicy) -> Result<()> {
+    match cache_policy.request_headers() {
+      Some(headers) => self.load_url_with_headers(url, headers),
+      None => self.load_url(url),
+    }
+  }
+
+  pub fn reload_bypassing_cache(&self) -> Result<()> {
+    // `Reload` has no cache option, the devtools protocol does
+    self.call_devtools_protocol_method("Page.reload", r#"{"ignoreCache":true}"#, |_| ())
+  }
+
   pub fn load_html(&self, html: &str) -> Result<()> {
     let html = HSTRING::from(html);
     unsafe { self.webview.NavigateToString(&html) }.map_err(Into::into)
@@ -1300,6 +1729,29 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn raise(&self) -> Result<()> {
+    self.set_z_order(HWND_TOP)
+  }
+
+  pub fn lower(&self) -> Result<()> {
+    self.set_z_order(HWND_BOTTOM)
+  }
+
+  fn set_z_order(&self, insert_after: HWND) -> Result<()> {
+    unsafe {
+      SetWindowPos(
+        self.hwnd,
+        insert_after,
+        0,
+        0,
+        0,
+        0,
+        SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+      )
+      .map_err(Into::into)
+    }
+  }
+
   pub fn focus(&self) -> Result<()> {
     unsafe {
       self
@@ -1458,6 +1910,17 @@ impl InnerWebView {
     )
   }
 
+  pub fn edit(&self, command: EditCommand) -> Result<()> {
+    self.eval(
+      command.script(),
+      None::<Box<dyn FnOnce(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn close(&self) -> Result<()> {
+    unsafe { self.controller.Close() }.map_err(Into::into)
+  }
+
   pub fn clear_all_browsing_data(&self) -> Result<()> {
     unsafe {
       self
@@ -1480,6 +1943,209 @@ impl InnerWebView {
     unsafe { set_background_color(&self.controller, background_color).map_err(Into::into) }
   }
 
+  pub fn process_memory_usage(&self) -> Result<Vec<ProcessMemoryUsage>> {
+    let env = self.env.cast::<ICoreWebView2Environment8>()?;
+    let mut usage = Vec::new();
+    unsafe {
+      let infos = env.GetProcessInfos()?;
+      let mut count = 0;
+      infos.Count(&mut count)?;
+      for i in 0..count {
+        let info = infos.GetValueAtIndex(i)?;
+
+        let mut process_id = 0;
+        info.ProcessId(&mut process_id)?;
+        let mut kind = COREWEBVIEW2_PROCESS_KIND::default();
+        info.Kind(&mut kind)?;
+
+        // the process may have exited since the infos were collected
+        let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id as u32)
+        else {
+          continue;
+        };
+        let cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        let mut counters = PROCESS_MEMORY_COUNTERS {
+          cb,
+          ..Default::default()
+        };
+        let res = GetProcessMemoryInfo(process, &mut counters, cb);
+        let _ = CloseHandle(process);
+        if res.is_err() {
+          continue;
+        }
+
+        usage.push(ProcessMemoryUsage {
+          process_id: process_id as u32,
+          kind: match kind {
+            COREWEBVIEW2_PROCESS_KIND_BROWSER => ProcessKind::Browser,
+            COREWEBVIEW2_PROCESS_KIND_RENDERER => ProcessKind::Renderer,
+            COREWEBVIEW2_PROCESS_KIND_GPU => ProcessKind::Gpu,
+            COREWEBVIEW2_PROCESS_KIND_UTILITY => ProcessKind::Utility,
+            _ => ProcessKind::Other,
+          },
+          working_set_size: counters.WorkingSetSize,
+          private_bytes: counters.PagefileUsage,
+        });
+      }
+    }
+
+    Ok(usage)
+  }
+
+  pub fn try_suspend<F: FnOnce(bool) + 'static>(&self, handler: F) -> Result<()> {
+    let webview = self.webview.cast::<ICoreWebView2_3>()?;
+    unsafe {
+      webview
+        .TrySuspend(&TrySuspendCompletedHandler::create(Box::new(
+          move |error_code, is_successful| {
+            error_code?;
+            handler(is_successful);
+            Ok(())
+          },
+        )))
+        .map_err(Into::into)
+    }
+  }
+
+  pub fn resume(&self) -> Result<()> {
+    let webview = self.webview.cast::<ICoreWebView2_3>()?;
+    unsafe { webview.Resume().map_err(Into::into) }
+  }
+
+  pub fn is_suspended(&self) -> Result<bool> {
+    let webview = self.webview.cast::<ICoreWebView2_3>()?;
+    let mut is_suspended = BOOL::default();
+    unsafe { webview.IsSuspended(&mut is_suspended)? };
+    Ok(is_suspended.as_bool())
+  }
+
+  pub fn call_devtools_protocol_method<F>(
+    &self,
+    method: &str,
+    params: &str,
+    handler: F,
+  ) -> Result<()>
+  where
+    F: FnOnce(Result<String>) + 'static,
+  {
+    let method = HSTRING::from(method);
+    let params = HSTRING::from(params);
+    unsafe {
+      self
+        .webview
+        .CallDevToolsProtocolMethod(
+          &method,
+          &params,
+          &CallDevToolsProtocolMethodCompletedHandler::create(Box::new(
+            move |error_code, result| {
+              handler(error_code.map(|_| result).map_err(Into::into));
+              Ok(())
+            },
+          )),
+        )
+        .map_err(Into::into)
+    }
+  }
+
+  pub fn save_page<F>(&self, path: &Path, format: SaveFormat, handler: F) -> Result<()>
+  where
+    F: FnOnce(Result<()>) + 'static,
+  {
+    match format {
+      SaveFormat::Mhtml => {
+        let path = path.to_path_buf();
+        self.call_devtools_protocol_method(
+          "Page.captureSnapshot",
+          r#"{"format":"mhtml"}"#,
+          move |result| {
+            handler(result.and_then(|result| {
+              let mhtml = crate::json::string_field(&result, "data").ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid snapshot")
+              })?;
+              std::fs::write(path, mhtml).map_err(Into::into)
+            }));
+          },
+        )
+      }
+      SaveFormat::Pdf => {
+        let webview = self.webview.cast::<ICoreWebView2_7>()?;
+        let path = HSTRING::from(path);
+        unsafe {
+          webview
+            .PrintToPdf(
+              &path,
+              None::<&ICoreWebView2PrintSettings>,
+              &PrintToPdfCompletedHandler::create(Box::new(move |error_code, is_successful| {
+                handler(error_code.map_err(Into::into).and_then(|_| {
+                  if is_successful {
+                    Ok(())
+                  } else {
+                    Err(std::io::Error::other("failed to print the page to PDF").into())
+                  }
+                }));
+                Ok(())
+              })),
+            )
+            .map_err(Into::into)
+        }
+      }
+      _ => Err(Error::UnsupportedSaveFormat),
+    }
+  }
+
+  pub fn back_forward_list(&self) -> Result<BackForwardList> {
+    Ok(BackForwardList::default())
+  }
+
+  pub fn serialize_session(&self) -> Result<Vec<u8>> {
+    Err(Error::UnsupportedSessionRestore)
+  }
+
+  pub fn restore_session(&self, _session: &[u8]) -> Result<()> {
+    Err(Error::UnsupportedSessionRestore)
+  }
+
+  pub fn add_devtools_protocol_event_handler<F>(&self, event: &str, handler: F) -> Result<()>
+  where
+    F: Fn(String) + 'static,
+  {
+    let event = HSTRING::from(event);
+    let mut token = EventRegistrationToken::default();
+    unsafe {
+      let receiver = self.webview.GetDevToolsProtocolEventReceiver(&event)?;
+      receiver.add_DevToolsProtocolEventReceived(
+        &DevToolsProtocolEventReceivedEventHandler::create(Box::new(move |_, args| {
+          if let Some(args) = args {
+            let mut params = PWSTR::null();
+            args.ParameterObjectAsJson(&mut params)?;
+            handler(take_pwstr(params));
+          }
+          Ok(())
+        })),
+        &mut token,
+      )?;
+    }
+    Ok(())
+  }
+
+  pub fn set_virtual_host_name_to_folder_mapping(
+    &self,
+    host: &str,
+    folder: &Path,
+    access: HostResourceAccessKind,
+  ) -> Result<()> {
+    unsafe { set_virtual_host_name_to_folder_mapping(&self.webview, host, folder, access) }
+  }
+
+  pub fn clear_virtual_host_name_to_folder_mapping(&self, host: &str) -> Result<()> {
+    let webview = self.webview.cast::<ICoreWebView2_3>()?;
+    unsafe {
+      webview
+        .ClearVirtualHostNameToFolderMapping(&HSTRING::from(host))
+        .map_err(Into::into)
+    }
+  }
+
   pub fn set_memory_usage_level(&self, level: MemoryUsageLevel) -> Result<()> {
     let webview = self.webview.cast::<ICoreWebView2_19>()?;
     // https://learn.microsoft.com/en-us/dotnet/api/microsoft.web.webview2.core.corewebview2memoryusagetargetlevel
@@ -1516,6 +2182,83 @@ pub enum ScrollBarStyle {
   FluentOverlay,
 }
 
+/// The memory used by one of the WebView2 processes,
+/// see [`WebViewExtWindows::process_memory_usage`](crate::WebViewExtWindows::process_memory_usage).
+#[derive(Debug, Clone, Copy)]
+pub struct ProcessMemoryUsage {
+  /// The process id.
+  pub process_id: u32,
+  /// What the process is used for.
+  pub kind: ProcessKind,
+  /// The physical memory currently used by the process, in bytes.
+  pub working_set_size: usize,
+  /// The memory committed for the process that can't be shared with other processes, in bytes.
+  pub private_bytes: usize,
+}
+
+/// The kind of a WebView2 process.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessKind {
+  /// The browser process, shared by all webviews using the same data directory.
+  Browser,
+  /// A renderer process, which runs the pages.
+  Renderer,
+  /// The GPU process.
+  Gpu,
+  /// A utility process, such as the network or audio service.
+  Utility,
+  /// Any other process.
+  Other,
+}
+
+/// An accelerator key event received by the handler set with
+/// [`WebViewBuilderExtWindows::with_accelerator_key_handler`](crate::WebViewBuilderExtWindows::with_accelerator_key_handler).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcceleratorKeyEvent {
+  /// The [virtual key code](https://learn.microsoft.com/en-us/windows/win32/inputdev/virtual-key-codes) of the key.
+  pub virtual_key: u32,
+  /// Whether the key was pressed or released.
+  pub pressed: bool,
+  /// Whether this is an auto-repeat of a key that is held down.
+  pub repeat: bool,
+  /// Whether a Ctrl key is held down.
+  pub ctrl: bool,
+  /// Whether a Shift key is held down.
+  pub shift: bool,
+  /// Whether an Alt key is held down.
+  pub alt: bool,
+}
+
+/// Controls which origins can access the resources of a virtual host,
+/// see [`WebViewBuilderExtWindows::with_virtual_host_name_to_folder_mapping`](crate::WebViewBuilderExtWindows::with_virtual_host_name_to_folder_mapping).
+#[derive(Clone, Copy, Default)]
+pub enum HostResourceAccessKind {
+  #[default]
+  /// Only documents loaded from the virtual host can access its resources.
+  Deny,
+
+  /// All origins can access the resources of the virtual host.
+  Allow,
+
+  /// Other origins can load resources such as images and scripts from the virtual host,
+  /// but cross-origin `fetch` and `XMLHttpRequest` requests are blocked by CORS.
+  DenyCors,
+}
+
+/// The order in which installed WebView2 runtimes are searched when creating the environment.
+#[derive(Clone, Copy, Default)]
+pub enum ReleaseChannelPreference {
+  #[default]
+  /// Use the most stable runtime found, starting with the WebView2 Runtime
+  /// and falling back to Beta, Dev and Canary Edge.
+  MostStable,
+
+  /// Use the least stable runtime found, starting with Canary Edge and
+  /// falling back to the WebView2 Runtime. Useful to test upcoming runtime changes.
+  LeastStable,
+}
+
 #[inline]
 fn load_url_with_headers(
   webview: &ICoreWebView2,
@@ -1548,6 +2291,27 @@ fn load_url_with_headers(
   Ok(())
 }
 
+/// Decode an image, like a PNG favicon, into non-premultiplied RGBA pixels.
+unsafe fn decode_icon(stream: &IStream) -> Result<Icon> {
+  let factory: IWICImagingFactory =
+    CoCreateInstance(&CLSID_WICImagingFactory, None, CLSCTX_INPROC_SERVER)?;
+  let decoder =
+    factory.CreateDecoderFromStream(stream, std::ptr::null(), WICDecodeMetadataCacheOnDemand)?;
+  let frame = decoder.GetFrame(0)?;
+  let source = WICConvertBitmapSource(&GUID_WICPixelFormat32bppRGBA, &frame)?;
+
+  let (mut width, mut height) = (0, 0);
+  source.GetSize(&mut width, &mut height)?;
+  let mut rgba = vec![0; width as usize * height as usize * 4];
+  source.CopyPixels(std::ptr::null(), width * 4, &mut rgba)?;
+
+  Ok(Icon {
+    rgba,
+    width,
+    height,
+  })
+}
+
 #[inline]
 unsafe fn set_background_color(
   controller: &ICoreWebView2Controller,
@@ -1582,6 +2346,23 @@ unsafe fn set_theme(webview: &ICoreWebView2, theme: Theme) -> Result<()> {
     .map_err(Into::into)
 }
 
+unsafe fn set_virtual_host_name_to_folder_mapping(
+  webview: &ICoreWebView2,
+  host: &str,
+  folder: &Path,
+  access: HostResourceAccessKind,
+) -> Result<()> {
+  let webview = webview.cast::<ICoreWebView2_3>()?;
+  let access = match access {
+    HostResourceAccessKind::Deny => COREWEBVIEW2_HOST_RESOURCE_ACCESS_KIND_DENY,
+    HostResourceAccessKind::Allow => COREWEBVIEW2_HOST_RESOURCE_ACCESS_KIND_ALLOW,
+    HostResourceAccessKind::DenyCors => COREWEBVIEW2_HOST_RESOURCE_ACCESS_KIND_DENY_CORS,
+  };
+  webview
+    .SetVirtualHostNameToFolderMapping(&HSTRING::from(host), &HSTRING::from(folder), access)
+    .map_err(Into::into)
+}
+
 #[inline]
 fn is_custom_protocol_uri(uri: &str, scheme: &'static str, protocol: &str) -> bool {
   let uri_len = uri.len();
@@ -1604,6 +2385,52 @@ pub fn platform_webview_version() -> Result<String> {
   Ok(take_pwstr(versioninfo))
 }
 
+/// Move or maximize the top-level window of `hwnd` like its title bar would.
+unsafe fn perform_drag_region_action(hwnd: HWND, action: DragRegionAction) {
+  let root = GetAncestor(hwnd, GA_ROOT);
+  match action {
+    DragRegionAction::Drag => {
+      let mut position = POINT::default();
+      let _ = GetCursorPos(&mut position);
+      // the webview holds the mouse capture since the press, give it back to the system move loop
+      let _ = ReleaseCapture();
+      let lparam = (position.x as u16 as u32 | (position.y as u16 as u32) << 16) as isize;
+      let _ = PostMessageW(
+        root,
+        WM_NCLBUTTONDOWN,
+        WPARAM(HTCAPTION as usize),
+        LPARAM(lparam),
+      );
+    }
+    DragRegionAction::ToggleMaximize => {
+      let command = if IsZoomed(root).as_bool() {
+        SW_RESTORE
+      } else {
+        SW_MAXIMIZE
+      };
+      let _ = ShowWindow(root, command);
+    }
+  }
+}
+
+pub fn open_with_default_handler(uri: &str) {
+  // `ShellExecuteW` also runs files and programs, only let it open URLs
+  if !crate::navigation::has_valid_scheme(uri) {
+    return;
+  }
+
+  unsafe {
+    ShellExecuteW(
+      HWND::default(),
+      w!("open"),
+      &HSTRING::from(uri),
+      PCWSTR::null(),
+      PCWSTR::null(),
+      SW_SHOWNORMAL,
+    );
+  }
+}
+
 #[inline]
 fn is_windows_7() -> bool {
   let v = windows_version::OsVersion::current();
@@ -0,0 +1,103 @@
+// Copyright 2020-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{ffi::c_void, ptr::null_mut};
+
+use objc2::{
+  declare_class, msg_send, msg_send_id,
+  mutability::InteriorMutable,
+  rc::Retained,
+  runtime::{AnyObject, NSObject},
+  ClassType, DeclaredClass,
+};
+use objc2_foundation::{
+  NSDictionary, NSInteger, NSKeyValueChangeKey, NSKeyValueObservingOptions,
+  NSObjectNSKeyValueObserverRegistration, NSObjectProtocol, NSString,
+};
+
+use crate::WryWebView;
+
+// WKFullscreenStateNotInFullscreen and WKFullscreenStateInFullscreen
+const NOT_IN_FULLSCREEN: NSInteger = 0;
+const IN_FULLSCREEN: NSInteger = 2;
+
+pub struct FullscreenStateObserverIvars {
+  pub object: Retained<WryWebView>,
+  pub handler: Box<dyn Fn(bool)>,
+}
+
+declare_class!(
+  pub struct FullscreenStateObserver;
+
+  unsafe impl ClassType for FullscreenStateObserver {
+    type Super = NSObject;
+    type Mutability = InteriorMutable;
+    const NAME: &'static str = "FullscreenStateObserver";
+  }
+
+  impl DeclaredClass for FullscreenStateObserver {
+    type Ivars = FullscreenStateObserverIvars;
+  }
+
+  unsafe impl FullscreenStateObserver {
+    #[method(observeValueForKeyPath:ofObject:change:context:)]
+    fn observe_value_for_key_path(
+      &self,
+      key_path: Option<&NSString>,
+      of_object: Option<&AnyObject>,
+      _change: Option<&NSDictionary<NSKeyValueChangeKey, AnyObject>>,
+      _context: *mut c_void,
+    ) {
+      if let (Some(key_path), Some(object)) = (key_path, of_object) {
+        if key_path.to_string() == "fullscreenState" {
+          let state: NSInteger = unsafe { msg_send![object, fullscreenState] };
+          // the entering and exiting states are transitions, only report where they end
+          match state {
+            IN_FULLSCREEN => (self.ivars().handler)(true),
+            NOT_IN_FULLSCREEN => (self.ivars().handler)(false),
+            _ => {}
+          }
+        }
+      }
+    }
+  }
+
+  unsafe impl NSObjectProtocol for FullscreenStateObserver {}
+);
+
+impl FullscreenStateObserver {
+  pub fn new(webview: Retained<WryWebView>, handler: Box<dyn Fn(bool)>) -> Retained<Self> {
+    let observer = Self::alloc().set_ivars(FullscreenStateObserverIvars {
+      object: webview,
+      handler,
+    });
+
+    let observer: Retained<Self> = unsafe { msg_send_id![super(observer), init] };
+
+    unsafe {
+      observer
+        .ivars()
+        .object
+        .addObserver_forKeyPath_options_context(
+          &observer,
+          &NSString::from_str("fullscreenState"),
+          NSKeyValueObservingOptions::NSKeyValueObservingOptionNew,
+          null_mut(),
+        );
+    }
+
+    observer
+  }
+}
+
+impl Drop for FullscreenStateObserver {
+  fn drop(&mut self) {
+    unsafe {
+      self
+        .ivars()
+        .object
+        .removeObserver_forKeyPath(self, &NSString::from_str("fullscreenState"));
+    }
+  }
+}
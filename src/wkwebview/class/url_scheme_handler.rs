@@ -187,7 +187,7 @@ extern "C" fn start_task(
                 task_uuid: Retained<NSUUID>,
                 webview_id: &str,
                 url: Retained<NSURL>,
-                sent_response: HttpResponse<Cow<'_, [u8]>>,
+                sent_response: HttpResponse<Cow<'static, [u8]>>,
               ) -> crate::Result<()> {
                 check_task_is_valid(&*webview, task_key, task_uuid.clone())?;
 
@@ -243,9 +243,19 @@ extern "C" fn start_task(
                 // Send data
                 let bytes = content.as_ptr() as *mut c_void;
                 let data = NSData::alloc();
-                // MIGRATE NOTE: we copied the content to the NSData because content will be freed
-                // when out of scope but NSData will also free the content when it's done and cause doube free.
-                let data = NSData::initWithBytes_length(data, bytes, content.len());
+                let data = match content {
+                  // static content outlives the NSData, so we can hand it over without a copy
+                  // as long as NSData doesn't try to free it.
+                  Cow::Borrowed(_) => NSData::initWithBytesNoCopy_length_freeWhenDone(
+                    data,
+                    NonNull::new(bytes).unwrap_or(NonNull::dangling()),
+                    content.len(),
+                    false,
+                  ),
+                  // MIGRATE NOTE: we copied the content to the NSData because content will be freed
+                  // when out of scope but NSData will also free the content when it's done and cause doube free.
+                  Cow::Owned(_) => NSData::initWithBytes_length(data, bytes, content.len()),
+                };
                 check_webview_id_valid(webview_id)?;
                 check_task_is_valid(&*webview, task_key, task_uuid.clone())?;
                 objc2::exception::catch(AssertUnwindSafe(|| {
@@ -2,13 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::sync::{Arc, Mutex};
+use std::{
+  cell::Cell,
+  sync::{Arc, Mutex},
+};
 
 use objc2::{
   declare_class, msg_send_id, mutability::MainThreadOnly, rc::Retained, runtime::NSObject,
   ClassType, DeclaredClass,
 };
-use objc2_foundation::{MainThreadMarker, NSObjectProtocol};
+use objc2_foundation::{MainThreadMarker, NSError, NSObjectProtocol};
 use objc2_web_kit::{
   WKDownload, WKNavigation, WKNavigationAction, WKNavigationActionPolicy, WKNavigationDelegate,
   WKNavigationResponse, WKNavigationResponsePolicy,
@@ -24,10 +27,11 @@ use crate::{
   wkwebview::{
     download::{navigation_download_action, navigation_download_response},
     navigation::{
-      did_commit_navigation, did_finish_navigation, navigation_policy, navigation_policy_response,
+      did_commit_navigation, did_fail_navigation, did_finish_navigation, navigation_policy,
+      navigation_policy_response,
     },
   },
-  PageLoadEvent, WryWebView,
+  NavigationError, PageLoadEvent, WryWebView,
 };
 
 use super::wry_download_delegate::WryDownloadDelegate;
@@ -38,6 +42,11 @@ pub struct WryNavigationDelegateIvars {
   pub navigation_policy_function: Box<dyn Fn(String, bool) -> bool>,
   pub download_delegate: Option<Retained<WryDownloadDelegate>>,
   pub on_page_load_handler: Option<Box<dyn Fn(PageLoadEvent)>>,
+  pub navigation_failed_handler: Option<Box<dyn Fn(NavigationError)>>,
+  pub error_page_handler: Option<Box<dyn Fn(&NavigationError) -> Option<String>>>,
+  /// Whether the next main frame navigation is the initial html or the error page, which skip
+  /// the navigation handler.
+  pub loading_html: Cell<bool>,
 }
 
 declare_class!(
@@ -94,6 +103,26 @@ declare_class!(
       did_commit_navigation(self, webview, navigation);
     }
 
+    #[method(webView:didFailProvisionalNavigation:withError:)]
+    fn did_fail_provisional_navigation(
+      &self,
+      webview: &WKWebView,
+      _navigation: Option<&WKNavigation>,
+      error: &NSError,
+    ) {
+      did_fail_navigation(self, webview, error);
+    }
+
+    #[method(webView:didFailNavigation:withError:)]
+    fn did_fail_navigation(
+      &self,
+      webview: &WKWebView,
+      _navigation: Option<&WKNavigation>,
+      error: &NSError,
+    ) {
+      did_fail_navigation(self, webview, error);
+    }
+
     #[method(webView:navigationAction:didBecomeDownload:)]
     fn navigation_download_action(
       &self,
@@ -126,6 +155,8 @@ impl WryNavigationDelegate {
     new_window_req_handler: Option<Box<dyn Fn(String) -> bool>>,
     download_delegate: Option<Retained<WryDownloadDelegate>>,
     on_page_load_handler: Option<Box<dyn Fn(PageLoadEvent, String)>>,
+    navigation_failed_handler: Option<Box<dyn Fn(NavigationError)>>,
+    error_page_handler: Option<Box<dyn Fn(&NavigationError) -> Option<String>>>,
     mtm: MainThreadMarker,
   ) -> Retained<Self> {
     let navigation_policy_function = Box::new(move |url: String, is_main_frame: bool| -> bool {
@@ -157,6 +188,9 @@ impl WryNavigationDelegate {
         has_download_handler,
         download_delegate,
         on_page_load_handler,
+        navigation_failed_handler,
+        error_page_handler,
+        loading_html: Cell::new(false),
       });
 
     unsafe { msg_send_id![super(delegate), init] }
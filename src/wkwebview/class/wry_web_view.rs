@@ -4,13 +4,17 @@
 
 use std::collections::HashMap;
 
+#[cfg(target_os = "macos")]
+use objc2::runtime::AnyObject;
 #[cfg(target_os = "macos")]
 use objc2::runtime::ProtocolObject;
 use objc2::{
   declare_class, mutability::MainThreadOnly, rc::Retained, runtime::Bool, ClassType, DeclaredClass,
 };
 #[cfg(target_os = "macos")]
-use objc2_app_kit::{NSDraggingDestination, NSEvent};
+use objc2_app_kit::{NSApplication, NSDraggingDestination, NSEvent, NSEventModifierFlags};
+#[cfg(target_os = "macos")]
+use objc2_foundation::MainThreadMarker;
 use objc2_foundation::{NSObjectProtocol, NSUUID};
 
 #[cfg(target_os = "ios")]
@@ -18,7 +22,7 @@ use crate::wkwebview::ios::WKWebView::WKWebView;
 #[cfg(target_os = "macos")]
 use crate::{
   wkwebview::{drag_drop, synthetic_mouse_events},
-  DragDropEvent,
+  DragDropEvent, KeyEvent,
 };
 #[cfg(target_os = "ios")]
 use objc2_ui_kit::UIEvent as NSEvent;
@@ -31,6 +35,12 @@ pub struct WryWebViewIvars {
   pub(crate) drag_drop_handler: Box<dyn Fn(DragDropEvent) -> bool>,
   #[cfg(target_os = "macos")]
   pub(crate) accept_first_mouse: objc2::runtime::Bool,
+  #[cfg(target_os = "macos")]
+  pub(crate) key_event_handler: Option<Box<dyn Fn(KeyEvent) -> bool>>,
+  #[cfg(target_os = "macos")]
+  pub(crate) focus_changed_handler: Option<Box<dyn Fn(bool)>>,
+  #[cfg(target_os = "macos")]
+  pub(crate) edit_key_equivalents: bool,
   pub(crate) custom_protocol_task_ids: HashMap<usize, Retained<NSUUID>>,
 }
 
@@ -53,6 +63,20 @@ declare_class!(
       &self,
       event: &NSEvent,
     ) -> Bool {
+      // key equivalents are offered to every view of the window, not only the focused one
+      #[cfg(target_os = "macos")]
+      if self.is_first_responder()
+        && is_key_equivalent(event)
+        && self.handle_key_event(event, true)
+      {
+        return Bool::YES;
+      }
+
+      #[cfg(target_os = "macos")]
+      if self.ivars().edit_key_equivalents && self.perform_edit_key_equivalent(event) {
+        return Bool::YES;
+      }
+
       // This is a temporary workaround for https://github.com/tauri-apps/tauri/issues/9426
       // FIXME: When the webview is a child webview, performKeyEquivalent always return YES
       // and stop propagating the event to the window, hence the menu shortcut won't be
@@ -114,6 +138,53 @@ declare_class!(
     }
   }
 
+  // Key events
+  #[cfg(target_os = "macos")]
+  unsafe impl WryWebView {
+    #[method(keyDown:)]
+    fn key_down(
+      &self,
+      event: &NSEvent,
+    ) {
+      // key equivalents were already reported in `performKeyEquivalent:`
+      if is_key_equivalent(event) || !self.handle_key_event(event, true) {
+        unsafe { objc2::msg_send![super(self), keyDown: event] }
+      }
+    }
+
+    #[method(keyUp:)]
+    fn key_up(
+      &self,
+      event: &NSEvent,
+    ) {
+      if !self.handle_key_event(event, false) {
+        unsafe { objc2::msg_send![super(self), keyUp: event] }
+      }
+    }
+  }
+
+  // Focus
+  #[cfg(target_os = "macos")]
+  unsafe impl WryWebView {
+    #[method(becomeFirstResponder)]
+    fn become_first_responder(&self) -> Bool {
+      let became: Bool = unsafe { objc2::msg_send![super(self), becomeFirstResponder] };
+      if let (true, Some(handler)) = (became.as_bool(), &self.ivars().focus_changed_handler) {
+        handler(true);
+      }
+      became
+    }
+
+    #[method(resignFirstResponder)]
+    fn resign_first_responder(&self) -> Bool {
+      let resigned: Bool = unsafe { objc2::msg_send![super(self), resignFirstResponder] };
+      if let (true, Some(handler)) = (resigned.as_bool(), &self.ivars().focus_changed_handler) {
+        handler(false);
+      }
+      resigned
+    }
+  }
+
   // Synthetic mouse events
   #[cfg(target_os = "macos")]
   unsafe impl WryWebView {
@@ -135,6 +206,96 @@ declare_class!(
   }
 );
 
+/// Whether AppKit offers the key down `event` to `performKeyEquivalent:` before `keyDown:`.
+#[cfg(target_os = "macos")]
+fn is_key_equivalent(event: &NSEvent) -> bool {
+  unsafe { event.modifierFlags() }.intersects(
+    NSEventModifierFlags::NSEventModifierFlagCommand
+      | NSEventModifierFlags::NSEventModifierFlagControl,
+  )
+}
+
+#[cfg(target_os = "macos")]
+impl WryWebView {
+  /// Whether the webview has the keyboard focus of its window.
+  fn is_first_responder(&self) -> bool {
+    self
+      .window()
+      .and_then(|window| window.firstResponder())
+      .is_some_and(|responder| {
+        Retained::as_ptr(&responder).cast::<WryWebView>() == self as *const WryWebView
+      })
+  }
+
+  /// Reports `event` to the key event handler, returns whether the handler swallowed it.
+  fn handle_key_event(&self, event: &NSEvent, pressed: bool) -> bool {
+    let Some(handler) = &self.ivars().key_event_handler else {
+      return false;
+    };
+
+    unsafe {
+      let flags = event.modifierFlags();
+      handler(KeyEvent {
+        native_key_code: event.keyCode() as u32,
+        pressed,
+        repeat: event.isARepeat(),
+        ctrl: flags.contains(NSEventModifierFlags::NSEventModifierFlagControl),
+        shift: flags.contains(NSEventModifierFlags::NSEventModifierFlagShift),
+        alt: flags.contains(NSEventModifierFlags::NSEventModifierFlagOption),
+        meta: flags.contains(NSEventModifierFlags::NSEventModifierFlagCommand),
+      })
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+impl WryWebView {
+  /// Sends the standard Edit menu shortcuts to their actions when the app's main menu
+  /// has no item for them, returns whether the event was handled.
+  fn perform_edit_key_equivalent(&self, event: &NSEvent) -> bool {
+    if !self.is_first_responder() {
+      return false;
+    }
+
+    unsafe {
+      let flags = event.modifierFlags();
+      let shift = flags.contains(NSEventModifierFlags::NSEventModifierFlagShift);
+      if !flags.contains(NSEventModifierFlags::NSEventModifierFlagCommand)
+        || flags.intersects(
+          NSEventModifierFlags::NSEventModifierFlagControl
+            | NSEventModifierFlags::NSEventModifierFlagOption,
+        )
+      {
+        return false;
+      }
+      let Some(key) = event.charactersIgnoringModifiers() else {
+        return false;
+      };
+
+      let action = match (key.to_string().to_lowercase().as_str(), shift) {
+        ("x", false) => objc2::sel!(cut:),
+        ("c", false) => objc2::sel!(copy:),
+        ("v", false) => objc2::sel!(paste:),
+        ("a", false) => objc2::sel!(selectAll:),
+        ("z", false) => objc2::sel!(undo:),
+        ("z", true) => objc2::sel!(redo:),
+        _ => return false,
+      };
+
+      let app = NSApplication::sharedApplication(MainThreadMarker::new().unwrap());
+      if app
+        .mainMenu()
+        .is_some_and(|menu| menu.performKeyEquivalent(event))
+      {
+        return true;
+      }
+      // undo and redo are handled by the window's undo manager, further up the responder chain
+      let sender: &AnyObject = self.as_ref();
+      app.sendAction_to_from(action, None, Some(sender))
+    }
+  }
+}
+
 // Custom Protocol Task Checker
 impl WryWebView {
   pub(crate) fn add_custom_task_key(&mut self, task_id: usize) -> Retained<NSUUID> {
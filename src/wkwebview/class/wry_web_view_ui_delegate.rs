@@ -22,9 +22,11 @@ use objc2_web_kit::{
   WKFrameInfo, WKMediaCaptureType, WKPermissionDecision, WKSecurityOrigin, WKUIDelegate,
 };
 
-use crate::WryWebView;
+use crate::{MediaCaptureKind, WryWebView};
 
-pub struct WryWebViewUIDelegateIvars {}
+pub struct WryWebViewUIDelegateIvars {
+  media_capture_permission_handler: Option<Box<dyn Fn(String, MediaCaptureKind) -> bool>>,
+}
 
 declare_class!(
   pub struct WryWebViewUIDelegate;
@@ -74,22 +76,50 @@ declare_class!(
     fn request_media_capture_permission(
       &self,
       _webview: &WryWebView,
-      _origin: &WKSecurityOrigin,
+      origin: &WKSecurityOrigin,
       _frame: &WKFrameInfo,
-      _capture_type: WKMediaCaptureType,
+      capture_type: WKMediaCaptureType,
       decision_handler: &Block<dyn Fn(WKPermissionDecision)>
     ) {
+      let allow = match &self.ivars().media_capture_permission_handler {
+        Some(handler) => {
+          let kind = match capture_type {
+            WKMediaCaptureType::Camera => MediaCaptureKind::Camera,
+            WKMediaCaptureType::Microphone => MediaCaptureKind::Microphone,
+            _ => MediaCaptureKind::CameraAndMicrophone,
+          };
+          let origin = unsafe {
+            match origin.port() {
+              0 => format!("{}://{}", origin.protocol(), origin.host()),
+              port => format!("{}://{}:{port}", origin.protocol(), origin.host()),
+            }
+          };
+          handler(origin, kind)
+        }
+        None => true,
+      };
+
       //https://developer.apple.com/documentation/webkit/wkpermissiondecision?language=objc
-      (*decision_handler).call((WKPermissionDecision::Grant,));
+      let decision = if allow {
+        WKPermissionDecision::Grant
+      } else {
+        WKPermissionDecision::Deny
+      };
+      (*decision_handler).call((decision,));
     }
   }
 );
 
 impl WryWebViewUIDelegate {
-  pub fn new(mtm: MainThreadMarker) -> Retained<Self> {
+  pub fn new(
+    media_capture_permission_handler: Option<Box<dyn Fn(String, MediaCaptureKind) -> bool>>,
+    mtm: MainThreadMarker,
+  ) -> Retained<Self> {
     let delegate = mtm
       .alloc::<WryWebViewUIDelegate>()
-      .set_ivars(WryWebViewUIDelegateIvars {});
+      .set_ivars(WryWebViewUIDelegateIvars {
+        media_capture_permission_handler,
+      });
     unsafe { msg_send_id![super(delegate), init] }
   }
 }
@@ -137,7 +137,6 @@ extern_methods!(
     #[method(setUIDelegate:)]
     pub unsafe fn setUIDelegate(&self, ui_delegate: Option<&ProtocolObject<dyn WKUIDelegate>>);
 
-    #[cfg(target_os = "macos")]
     // #[cfg(feature = "WKBackForwardList")]
     #[method_id(@__retain_semantics Other backForwardList)]
     pub unsafe fn backForwardList(&self) -> Retained<WKBackForwardList>;
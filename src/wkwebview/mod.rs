@@ -21,6 +21,7 @@ pub use class::wry_web_view::WryWebView;
 use class::wry_web_view_parent::WryWebViewParent;
 use class::{
   document_title_changed_observer::*,
+  fullscreen_state_observer::FullscreenStateObserver,
   url_scheme_handler,
   wry_download_delegate::WryDownloadDelegate,
   wry_navigation_delegate::WryNavigationDelegate,
@@ -38,14 +39,17 @@ use objc2::{
   ClassType, DeclaredClass,
 };
 #[cfg(target_os = "macos")]
-use objc2_app_kit::{NSApplication, NSAutoresizingMaskOptions, NSTitlebarSeparatorStyle, NSView};
+use objc2_app_kit::{
+  NSAppearance, NSAppearanceCustomization, NSAppearanceNameAqua, NSAppearanceNameDarkAqua,
+  NSApplication, NSAutoresizingMaskOptions, NSTitlebarSeparatorStyle, NSView, NSWindowOrderingMode,
+};
 #[cfg(target_os = "macos")]
 use objc2_foundation::CGSize;
 use objc2_foundation::{
-  ns_string, CGPoint, CGRect, MainThreadMarker, NSArray, NSBundle, NSDate, NSError, NSHTTPCookie,
-  NSHTTPCookieSameSiteLax, NSHTTPCookieSameSiteStrict, NSJSONSerialization, NSMutableURLRequest,
-  NSNumber, NSObjectNSKeyValueCoding, NSObjectProtocol, NSString, NSUTF8StringEncoding, NSURL,
-  NSUUID,
+  ns_string, CGPoint, CGRect, MainThreadMarker, NSArray, NSBundle, NSData, NSDate, NSError,
+  NSHTTPCookie, NSHTTPCookieSameSiteLax, NSHTTPCookieSameSiteStrict, NSJSONSerialization,
+  NSMutableURLRequest, NSNumber, NSObjectNSKeyValueCoding, NSObjectProtocol, NSString,
+  NSURLRequestCachePolicy, NSUTF8StringEncoding, NSURL, NSUUID,
 };
 #[cfg(target_os = "ios")]
 use objc2_ui_kit::{UIScrollView, UIViewAutoresizing};
@@ -63,19 +67,23 @@ use crate::wkwebview::ios::WKWebView::WKWebView;
 use objc2_web_kit::WKWebView;
 
 use objc2_web_kit::{
-  WKAudiovisualMediaTypes, WKURLSchemeHandler, WKUserContentController, WKUserScript,
-  WKUserScriptInjectionTime, WKWebViewConfiguration, WKWebsiteDataStore,
+  WKAudiovisualMediaTypes, WKBackForwardListItem, WKContentRuleList, WKContentRuleListStore,
+  WKURLSchemeHandler, WKUserContentController, WKUserScript, WKUserScriptInjectionTime,
+  WKWebViewConfiguration, WKWebsiteDataStore,
 };
 use once_cell::sync::Lazy;
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
 
 use std::{
+  cell::{Cell, RefCell},
   collections::{HashMap, HashSet},
   ffi::{c_void, CString},
   net::Ipv4Addr,
   os::raw::c_char,
   panic::AssertUnwindSafe,
+  path::Path,
   ptr::{null_mut, NonNull},
+  rc::Rc,
   str::{self, FromStr},
   sync::{Arc, Mutex},
 };
@@ -88,7 +96,12 @@ use crate::{
   },
 };
 
-use crate::{Error, Rect, RequestAsyncResponder, Result, WebViewAttributes, RGBA};
+#[cfg(target_os = "macos")]
+use crate::{drag_region::DragRegionAction, Theme};
+use crate::{
+  BackForwardList, CachePolicy, EditCommand, Error, HistoryItem, Rect, RequestAsyncResponder,
+  Result, SaveFormat, WebViewAttributes, RGBA,
+};
 
 use http::Request;
 
@@ -127,6 +140,9 @@ pub(crate) struct InnerWebView {
   document_title_changed_observer: Option<Retained<DocumentTitleChangedObserver>>,
   #[allow(dead_code)]
   // We need this the keep the reference count
+  fullscreen_state_observer: Option<Retained<FullscreenStateObserver>>,
+  #[allow(dead_code)]
+  // We need this the keep the reference count
   navigation_policy_delegate: Retained<WryNavigationDelegate>,
   #[allow(dead_code)]
   // We need this the keep the reference count
@@ -242,6 +258,7 @@ impl InnerWebView {
 
       // WebView and manager
       let manager = config.userContentController();
+
       let webview = mtm.alloc::<WryWebView>().set_ivars(WryWebViewIvars {
         is_child,
         #[cfg(target_os = "macos")]
@@ -251,6 +268,12 @@ impl InnerWebView {
         },
         #[cfg(target_os = "macos")]
         accept_first_mouse: Bool::new(attributes.accept_first_mouse),
+        #[cfg(target_os = "macos")]
+        key_event_handler: attributes.key_event_handler,
+        #[cfg(target_os = "macos")]
+        focus_changed_handler: attributes.focus_changed_handler,
+        #[cfg(target_os = "macos")]
+        edit_key_equivalents: pl_attrs.edit_key_equivalents,
         custom_protocol_task_ids: HashMap::new(),
       });
 
@@ -280,6 +303,10 @@ impl InnerWebView {
         ns_string!("allowsPictureInPictureMediaPlayback"),
       );
 
+      if let Some(size) = attributes.minimum_font_size {
+        _preference.setMinimumFontSize(size as _);
+      }
+
       #[cfg(target_os = "ios")]
       config.setValue_forKey(Some(&_yes), ns_string!("allowsInlineMediaPlayback"));
 
@@ -289,6 +316,15 @@ impl InnerWebView {
         );
       }
 
+      // the default webpage preferences are only available on macOS 11+ and iOS 14+
+      if !attributes.javascript_enabled
+        && config.respondsToSelector(objc2::sel!(defaultWebpagePreferences))
+      {
+        config
+          .defaultWebpagePreferences()
+          .setAllowsContentJavaScript(false);
+      }
+
       #[cfg(feature = "transparent")]
       if attributes.transparent {
         let no = NSNumber::numberWithBool(false);
@@ -298,7 +334,12 @@ impl InnerWebView {
 
       #[cfg(feature = "fullscreen")]
       // Equivalent Obj-C:
-      _preference.setValue_forKey(Some(&_yes), ns_string!("fullScreenEnabled"));
+      _preference.setValue_forKey(
+        Some(&NSNumber::numberWithBool(
+          attributes.fullscreen_content_allowed,
+        )),
+        ns_string!("fullScreenEnabled"),
+      );
 
       #[cfg(target_os = "macos")]
       let webview = {
@@ -360,9 +401,6 @@ impl InnerWebView {
           );
         }
 
-        // allowsBackForwardNavigation
-        webview.setAllowsBackForwardNavigationGestures(attributes.back_forward_navigation_gestures);
-
         // tabFocusesLinks
         _preference.setValue_forKey(Some(&_yes), ns_string!("tabFocusesLinks"));
       }
@@ -380,6 +418,9 @@ impl InnerWebView {
         scroll_view.setBounces(false)
       }
 
+      // allowsBackForwardNavigation
+      webview.setAllowsBackForwardNavigationGestures(attributes.back_forward_navigation_gestures);
+
       if !attributes.visible {
         webview.setHidden(true);
       }
@@ -396,6 +437,17 @@ impl InnerWebView {
         _preference.setValue_forKey(Some(&_yes), &dev);
       }
 
+      // Drag regions
+      #[cfg(target_os = "macos")]
+      if attributes.drag_regions {
+        let webview = objc2::rc::Weak::from_retained(&webview);
+        let _ = pl_attrs.drag_region_target.set(Box::new(move |action| {
+          if let Some(webview) = webview.load() {
+            perform_drag_region_action(&webview, action);
+          }
+        }));
+      }
+
       // Message handler
       let ipc_handler_delegate = if let Some(ipc_handler) = attributes.ipc_handler {
         let delegate = WryWebViewDelegate::new(manager.clone(), ipc_handler, mtm);
@@ -413,6 +465,12 @@ impl InnerWebView {
           None
         };
 
+      // Fullscreen changed handler, `fullscreenState` is only available on macOS 13 and iOS 16
+      let fullscreen_state_observer = attributes
+        .fullscreen_changed_handler
+        .filter(|_| NSObject::respondsToSelector(&webview, objc2::sel!(fullscreenState)))
+        .map(|handler| FullscreenStateObserver::new(webview.clone(), handler));
+
       let pending_scripts = Arc::new(Mutex::new(Some(Vec::new())));
       let has_download_handler = attributes.download_started_handler.is_some();
       // Download handler
@@ -437,14 +495,21 @@ impl InnerWebView {
         attributes.new_window_req_handler,
         download_delegate.clone(),
         attributes.on_page_load_handler,
+        attributes.navigation_failed_handler,
+        attributes.error_page_handler,
         mtm,
       );
+      navigation_policy_delegate
+        .ivars()
+        .loading_html
+        .set(attributes.url.is_none() && attributes.html.is_some());
 
       let proto_navigation_policy_delegate =
         ProtocolObject::from_ref(navigation_policy_delegate.as_ref());
       webview.setNavigationDelegate(Some(proto_navigation_policy_delegate));
 
-      let ui_delegate: Retained<WryWebViewUIDelegate> = WryWebViewUIDelegate::new(mtm);
+      let ui_delegate: Retained<WryWebViewUIDelegate> =
+        WryWebViewUIDelegate::new(attributes.media_capture_permission_handler, mtm);
       let proto_ui_delegate = ProtocolObject::from_ref(ui_delegate.as_ref());
       webview.setUIDelegate(Some(proto_ui_delegate));
 
@@ -468,6 +533,7 @@ impl InnerWebView {
         pending_scripts,
         ipc_handler_delegate,
         document_title_changed_observer,
+        fullscreen_state_observer,
         navigation_policy_delegate,
         download_delegate,
         ui_delegate,
@@ -476,11 +542,12 @@ impl InnerWebView {
       };
 
       // Initialize scripts
-      w.init(
-r#"Object.defineProperty(window, 'ipc', {
+      w.init(&crate::ipc::init_script(
+        attributes.ipc_origins.as_deref(),
+        r#"Object.defineProperty(window, 'ipc', {
   value: Object.freeze({postMessage: function(s) {window.webkit.messageHandlers.ipc.postMessage(s);}})
 });"#,
-      );
+      ));
       for js in attributes.initialization_scripts {
         w.init(&js);
       }
@@ -490,9 +557,33 @@ r#"Object.defineProperty(window, 'ipc', {
         w.set_user_agent(user_agent.as_str())
       }
 
-      // Navigation
-      if let Some(url) = attributes.url {
-        w.navigate_to_url(url.as_str(), attributes.headers)?;
+      #[cfg(target_os = "macos")]
+      if let Some(theme) = pl_attrs.theme {
+        w.set_theme(theme);
+      }
+
+      // Navigation, once the content filters are ready so that the first page is filtered too
+      let content_filters = attributes
+        .context
+        .as_deref()
+        .map(|context| context.content_filters.clone())
+        .unwrap_or_default();
+      if !content_filters.is_empty() {
+        let webview = w.webview.clone();
+        let (url, headers, html) = (attributes.url, attributes.headers, attributes.html);
+        add_content_filters(
+          &manager,
+          content_filters.into_iter(),
+          Box::new(move || {
+            if let Some(url) = url {
+              load_url(&webview, &url, headers, CachePolicy::Default);
+            } else if let Some(html) = html {
+              load_html(&webview, &html);
+            }
+          }),
+        );
+      } else if let Some(url) = attributes.url {
+        w.navigate_to_url(url.as_str(), attributes.headers, CachePolicy::Default)?;
       } else if let Some(html) = attributes.html {
         w.navigate_to_string(&html);
       }
@@ -618,11 +709,22 @@ r#"Object.defineProperty(window, 'ipc', {
   }
 
   pub fn load_url(&self, url: &str) -> crate::Result<()> {
-    self.navigate_to_url(url, None)
+    self.navigate_to_url(url, None, CachePolicy::Default)
   }
 
   pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) -> crate::Result<()> {
-    self.navigate_to_url(url, Some(headers))
+    self.navigate_to_url(url, Some(headers), CachePolicy::Default)
+  }
+
+  pub fn load_url_with(&self, url: &str, cache_policy: CachePolicy) -> crate::Result<()> {
+    self.navigate_to_url(url, None, cache_policy)
+  }
+
+  pub fn reload_bypassing_cache(&self) -> crate::Result<()> {
+    unsafe {
+      self.webview.reloadFromOrigin();
+    }
+    Ok(())
   }
 
   pub fn load_html(&self, html: &str) -> crate::Result<()> {
@@ -630,6 +732,11 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
+  pub fn close(&self) -> Result<()> {
+    // the webview is torn down when dropped
+    Ok(())
+  }
+
   pub fn clear_all_browsing_data(&self) -> Result<()> {
     unsafe {
       let config = self.webview.configuration();
@@ -642,43 +749,158 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
-  fn navigate_to_url(&self, url: &str, headers: Option<http::HeaderMap>) -> crate::Result<()> {
-    // Safety: objc runtime calls are unsafe
+  fn navigate_to_url(
+    &self,
+    url: &str,
+    headers: Option<http::HeaderMap>,
+    cache_policy: CachePolicy,
+  ) -> crate::Result<()> {
+    load_url(&self.webview, url, headers, cache_policy);
+    Ok(())
+  }
+
+  fn navigate_to_string(&self, html: &str) {
+    load_html(&self.webview, html);
+  }
+
+  fn set_user_agent(&self, user_agent: &str) {
     unsafe {
-      let url = NSURL::URLWithString(&NSString::from_str(url)).unwrap();
-      let mut request = NSMutableURLRequest::requestWithURL(&url);
-      if let Some(headers) = headers {
-        for (name, value) in headers.iter() {
-          let key = NSString::from_str(name.as_str());
-          let value = NSString::from_str(value.to_str().unwrap_or_default());
-          request.addValue_forHTTPHeaderField(&value, &key);
+      self
+        .webview
+        .setCustomUserAgent(Some(&NSString::from_str(user_agent)));
+    }
+  }
+
+  pub fn print(&self) -> crate::Result<()> {
+    self.print_with_options(&PrintOptions::default())
+  }
+
+  pub fn edit(&self, command: EditCommand) -> crate::Result<()> {
+    let sender = std::ptr::null::<AnyObject>();
+    // the standard edit actions of NSResponder, and of UIResponder on iOS
+    unsafe {
+      match command {
+        EditCommand::Copy => objc2::msg_send![&self.webview, copy: sender],
+        EditCommand::Cut => objc2::msg_send![&self.webview, cut: sender],
+        EditCommand::Paste => objc2::msg_send![&self.webview, paste: sender],
+        EditCommand::SelectAll => objc2::msg_send![&self.webview, selectAll: sender],
+      }
+    }
+    Ok(())
+  }
+
+  pub fn save_page<F>(&self, path: &Path, format: SaveFormat, handler: F) -> crate::Result<()>
+  where
+    F: FnOnce(crate::Result<()>) + 'static,
+  {
+    let selector = match format {
+      SaveFormat::WebArchive => objc2::sel!(createWebArchiveDataWithCompletionHandler:),
+      SaveFormat::Pdf => objc2::sel!(createPDFWithConfiguration:completionHandler:),
+      _ => return Err(Error::UnsupportedSaveFormat),
+    };
+    // Both are only available on macOS 11+ and iOS 14+
+    if !self.webview.respondsToSelector(selector) {
+      return Err(Error::UnsupportedSaveFormat);
+    }
+
+    let path = path.to_path_buf();
+    let handler = RefCell::new(Some(handler));
+    let block = block2::RcBlock::new(move |data: *mut NSData, error: *mut NSError| {
+      let Some(handler) = handler.take() else {
+        return;
+      };
+      let result = match unsafe { data.as_ref() } {
+        Some(data) => std::fs::write(&path, data.bytes()).map_err(Into::into),
+        None => {
+          let description = unsafe { error.as_ref() }
+            .map(|error| error.localizedDescription().to_string())
+            .unwrap_or_default();
+          Err(std::io::Error::other(description).into())
         }
+      };
+      handler(result);
+    });
+
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      if format == SaveFormat::Pdf {
+        let () = objc2::msg_send![
+          &self.webview,
+          createPDFWithConfiguration: std::ptr::null::<AnyObject>(),
+          completionHandler: &*block
+        ];
+      } else {
+        let () =
+          objc2::msg_send![&self.webview, createWebArchiveDataWithCompletionHandler: &*block];
       }
-      self.webview.loadRequest(&request);
     }
 
     Ok(())
   }
 
-  fn navigate_to_string(&self, html: &str) {
+  pub fn back_forward_list(&self) -> crate::Result<BackForwardList> {
     // Safety: objc runtime calls are unsafe
     unsafe {
-      self
-        .webview
-        .loadHTMLString_baseURL(&NSString::from_str(html), None);
+      let history_item = |item: &WKBackForwardListItem| HistoryItem {
+        url: item
+          .URL()
+          .absoluteString()
+          .map(|url| url.to_string())
+          .unwrap_or_default(),
+        title: item
+          .title()
+          .map(|title| title.to_string())
+          .unwrap_or_default(),
+      };
+
+      let list = self.webview.backForwardList();
+      Ok(BackForwardList {
+        back: list.backList().iter().map(history_item).collect(),
+        current: list.currentItem().as_deref().map(history_item),
+        forward: list.forwardList().iter().map(history_item).collect(),
+      })
     }
   }
 
-  fn set_user_agent(&self, user_agent: &str) {
+  pub fn serialize_session(&self) -> crate::Result<Vec<u8>> {
+    // `interactionState` is only available on macOS 12+ and iOS 15+
+    if !self
+      .webview
+      .respondsToSelector(objc2::sel!(interactionState))
+    {
+      return Err(Error::UnsupportedSessionRestore);
+    }
+
+    // Safety: objc runtime calls are unsafe
     unsafe {
-      self
-        .webview
-        .setCustomUserAgent(Some(&NSString::from_str(user_agent)));
+      let Some(state) = self.webview.interactionState() else {
+        return Ok(Vec::new());
+      };
+      let is_data: bool = objc2::msg_send![&state, isKindOfClass: NSData::class()];
+      if !is_data {
+        return Ok(Vec::new());
+      }
+      let data: Retained<NSData> = Retained::cast(state);
+      Ok(data.bytes().to_vec())
     }
   }
 
-  pub fn print(&self) -> crate::Result<()> {
-    self.print_with_options(&PrintOptions::default())
+  pub fn restore_session(&self, session: &[u8]) -> crate::Result<()> {
+    if !self
+      .webview
+      .respondsToSelector(objc2::sel!(setInteractionState:))
+    {
+      return Err(Error::UnsupportedSessionRestore);
+    }
+    if session.is_empty() {
+      return Ok(());
+    }
+
+    let data = NSData::with_bytes(session);
+    let state: &AnyObject = &data;
+    // Safety: objc runtime calls are unsafe
+    unsafe { self.webview.setInteractionState(Some(state)) };
+    Ok(())
   }
 
   pub fn print_with_options(&self, _options: &PrintOptions) -> crate::Result<()> {
@@ -758,6 +980,35 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
+  #[cfg(target_os = "ios")]
+  pub fn scroll_position(&self) -> LogicalPosition<f64> {
+    unsafe {
+      let scroll_view: Retained<UIScrollView> = objc2::msg_send_id![&self.webview, scrollView];
+      let offset = scroll_view.contentOffset();
+      // the offset is negative by the insets when the page is scrolled to the top
+      let inset = scroll_view.adjustedContentInset();
+      let zoom = scroll_view.zoomScale();
+      LogicalPosition::new(
+        (offset.x + inset.left) / zoom,
+        (offset.y + inset.top) / zoom,
+      )
+    }
+  }
+
+  #[cfg(target_os = "ios")]
+  pub fn set_scroll_position(&self, position: LogicalPosition<f64>, smooth: bool) {
+    unsafe {
+      let scroll_view: Retained<UIScrollView> = objc2::msg_send_id![&self.webview, scrollView];
+      let inset = scroll_view.adjustedContentInset();
+      let zoom = scroll_view.zoomScale();
+      let offset = CGPoint::new(
+        position.x * zoom - inset.left,
+        position.y * zoom - inset.top,
+      );
+      scroll_view.setContentOffset_animated(offset, smooth);
+    }
+  }
+
   pub fn set_background_color(&self, _background_color: RGBA) -> Result<()> {
     Ok(())
   }
@@ -806,6 +1057,59 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
+  pub fn raise(&self) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    if self.is_child {
+      if let Some(parent_view) = unsafe { self.webview.superview() } {
+        unsafe {
+          parent_view.addSubview_positioned_relativeTo(
+            &self.webview,
+            NSWindowOrderingMode::NSWindowAbove,
+            None,
+          )
+        };
+      }
+    }
+    #[cfg(target_os = "ios")]
+    if let Some(parent_view) = self.webview.superview() {
+      unsafe { parent_view.bringSubviewToFront(&self.webview) };
+    }
+
+    Ok(())
+  }
+
+  pub fn lower(&self) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    if self.is_child {
+      if let Some(parent_view) = unsafe { self.webview.superview() } {
+        unsafe {
+          parent_view.addSubview_positioned_relativeTo(
+            &self.webview,
+            NSWindowOrderingMode::NSWindowBelow,
+            None,
+          )
+        };
+      }
+    }
+    #[cfg(target_os = "ios")]
+    if let Some(parent_view) = self.webview.superview() {
+      unsafe { parent_view.sendSubviewToBack(&self.webview) };
+    }
+
+    Ok(())
+  }
+
+  #[cfg(target_os = "macos")]
+  pub fn set_theme(&self, theme: Theme) {
+    // `prefers-color-scheme` follows the effective appearance of the view
+    let appearance = match theme {
+      Theme::Dark => NSAppearance::appearanceNamed(unsafe { NSAppearanceNameDarkAqua }),
+      Theme::Light => NSAppearance::appearanceNamed(unsafe { NSAppearanceNameAqua }),
+      Theme::Auto => None,
+    };
+    unsafe { self.webview.setAppearance(appearance.as_deref()) };
+  }
+
   pub fn focus(&self) -> Result<()> {
     #[cfg(target_os = "macos")]
     {
@@ -960,6 +1264,139 @@ pub fn platform_webview_version() -> Result<String> {
   }
 }
 
+/// Move or zoom the window of `webview` like its title bar would.
+#[cfg(target_os = "macos")]
+fn perform_drag_region_action(webview: &WryWebView, action: DragRegionAction) {
+  let Some(window) = webview.window() else {
+    return;
+  };
+
+  match action {
+    DragRegionAction::Drag => {
+      // the last event dequeued, the press or a drag that followed it
+      let app = NSApplication::sharedApplication(MainThreadMarker::from(webview));
+      if let Some(event) = app.currentEvent() {
+        window.performWindowDragWithEvent(&event);
+      }
+    }
+    DragRegionAction::ToggleMaximize => window.zoom(None),
+  }
+}
+
+pub fn open_with_default_handler(uri: &str) {
+  unsafe {
+    let Some(url) = NSURL::URLWithString(&NSString::from_str(uri)) else {
+      return;
+    };
+
+    #[cfg(target_os = "macos")]
+    objc2_app_kit::NSWorkspace::sharedWorkspace().openURL(&url);
+
+    #[cfg(target_os = "ios")]
+    {
+      let app: Retained<AnyObject> =
+        objc2::msg_send_id![objc2::class!(UIApplication), sharedApplication];
+      let options = objc2_foundation::NSDictionary::<NSString, AnyObject>::new();
+      let _: () = objc2::msg_send![
+        &app,
+        openURL: &*url,
+        options: &*options,
+        completionHandler: std::ptr::null::<c_void>()
+      ];
+    }
+  }
+}
+
+fn load_url(
+  webview: &WryWebView,
+  url: &str,
+  headers: Option<http::HeaderMap>,
+  cache_policy: CachePolicy,
+) {
+  // Safety: objc runtime calls are unsafe
+  unsafe {
+    let url = NSURL::URLWithString(&NSString::from_str(url)).unwrap();
+    let mut request = NSMutableURLRequest::requestWithURL(&url);
+    if cache_policy == CachePolicy::BypassCache {
+      request.setCachePolicy(NSURLRequestCachePolicy::NSURLRequestReloadIgnoringLocalCacheData);
+    }
+    if let Some(headers) = headers {
+      for (name, value) in headers.iter() {
+        let key = NSString::from_str(name.as_str());
+        let value = NSString::from_str(value.to_str().unwrap_or_default());
+        request.addValue_forHTTPHeaderField(&value, &key);
+      }
+    }
+    webview.loadRequest(&request);
+  }
+}
+
+fn load_html(webview: &WryWebView, html: &str) {
+  // Safety: objc runtime calls are unsafe
+  unsafe {
+    webview.loadHTMLString_baseURL(&NSString::from_str(html), None);
+  }
+}
+
+/// Add the content rule lists to `manager` one after the other, then call `ready`, even if
+/// some failed to compile. A rule list is only compiled when the store doesn't have it yet.
+fn add_content_filters(
+  manager: &WKUserContentController,
+  mut filters: std::vec::IntoIter<(String, String)>,
+  ready: Box<dyn FnOnce()>,
+) {
+  let store = unsafe { WKContentRuleListStore::defaultStore() };
+  let (Some(store), Some((identifier, rules))) = (store, filters.next()) else {
+    return ready();
+  };
+
+  // add the rule list if there is one, and move on to the next
+  let manager = manager.retain();
+  let next = Cell::new(Some((filters, ready)));
+  let done = Rc::new(move |list: *mut WKContentRuleList| {
+    if let Some(list) = unsafe { list.as_ref() } {
+      unsafe { manager.addContentRuleList(list) };
+    }
+    if let Some((filters, ready)) = next.take() {
+      add_content_filters(&manager, filters, ready);
+    }
+  });
+
+  let identifier = NSString::from_str(&identifier);
+  let lookup_handler = {
+    let store = store.clone();
+    let identifier = identifier.clone();
+    block2::RcBlock::new(move |list: *mut WKContentRuleList, _error: *mut NSError| {
+      if !list.is_null() {
+        return done(list);
+      }
+
+      let done = done.clone();
+      let compile_handler =
+        block2::RcBlock::new(move |list: *mut WKContentRuleList, _error: *mut NSError| {
+          #[cfg(feature = "tracing")]
+          if list.is_null() {
+            tracing::warn!("Failed to compile the content filter.");
+          }
+          done(list);
+        });
+      unsafe {
+        store.compileContentRuleListForIdentifier_encodedContentRuleList_completionHandler(
+          Some(&identifier),
+          Some(&NSString::from_str(&rules)),
+          Some(&compile_handler),
+        );
+      }
+    })
+  };
+  unsafe {
+    store.lookUpContentRuleListForIdentifier_completionHandler(
+      Some(&identifier),
+      Some(&lookup_handler),
+    );
+  }
+}
+
 impl Drop for InnerWebView {
   fn drop(&mut self) {
     WEBVIEW_IDS.lock().unwrap().remove(&self.id);
@@ -1,5 +1,5 @@
-use objc2::DeclaredClass;
-use objc2_foundation::{NSObjectProtocol, NSString};
+use objc2::{rc::Retained, ClassType, DeclaredClass};
+use objc2_foundation::{ns_string, NSError, NSHTTPURLResponse, NSObjectProtocol, NSString};
 use objc2_web_kit::{
   WKNavigation, WKNavigationAction, WKNavigationActionPolicy, WKNavigationResponse,
   WKNavigationResponsePolicy,
@@ -10,7 +10,7 @@ use crate::wkwebview::ios::WKWebView::WKWebView;
 #[cfg(target_os = "macos")]
 use objc2_web_kit::WKWebView;
 
-use crate::PageLoadEvent;
+use crate::{NavigationError, NavigationErrorKind, PageLoadEvent};
 
 use super::class::wry_navigation_delegate::WryNavigationDelegate;
 
@@ -46,6 +46,63 @@ pub(crate) fn did_finish_navigation(
   }
 }
 
+pub(crate) fn did_fail_navigation(
+  this: &WryNavigationDelegate,
+  webview: &WKWebView,
+  error: &NSError,
+) {
+  let ivars = this.ivars();
+  if ivars.navigation_failed_handler.is_none() && ivars.error_page_handler.is_none() {
+    return;
+  }
+
+  let domain = error.domain().to_string();
+  let code = error.code();
+
+  // WebKitErrorFrameLoadInterruptedByPolicyChange, the load was turned
+  // into a download or stopped by the navigation handler
+  if domain == "WebKitErrorDomain" && code == 102 {
+    return;
+  }
+
+  // See the NSURLErrorDomain codes in NSURLError.h
+  let kind = match (domain.as_str(), code) {
+    ("NSURLErrorDomain", -999) => NavigationErrorKind::Cancelled,
+    ("NSURLErrorDomain", -1001) => NavigationErrorKind::Timeout,
+    ("NSURLErrorDomain", -1003 | -1006) => NavigationErrorKind::HostNotFound,
+    ("NSURLErrorDomain", -1004 | -1005 | -1009 | -1018 | -1020) => {
+      NavigationErrorKind::ConnectionFailed
+    }
+    ("NSURLErrorDomain", -1206..=-1200) => NavigationErrorKind::Tls,
+    _ => NavigationErrorKind::Other,
+  };
+
+  let url = unsafe {
+    error
+      .userInfo()
+      .objectForKey(ns_string!("NSErrorFailingURLStringKey"))
+      .map(|url| Retained::cast::<NSString>(url).to_string())
+      .unwrap_or_default()
+  };
+
+  let error = NavigationError { url, kind };
+
+  let html = ivars
+    .error_page_handler
+    .as_ref()
+    .filter(|_| error.has_error_page())
+    .and_then(|error_page_handler| error_page_handler(&error));
+  if let Some(html) = html {
+    // without a base URL the page is `about:blank`, with an opaque origin
+    ivars.loading_html.set(true);
+    unsafe { webview.loadHTMLString_baseURL(&NSString::from_str(&html), None) };
+  }
+
+  if let Some(navigation_failed_handler) = &ivars.navigation_failed_handler {
+    navigation_failed_handler(error);
+  }
+}
+
 // Navigation handler
 pub(crate) fn navigation_policy(
   this: &WryNavigationDelegate,
@@ -73,6 +130,10 @@ pub(crate) fn navigation_policy(
       } else {
         (*handler).call((WKNavigationActionPolicy::Cancel,));
       }
+    } else if this.ivars().loading_html.take() && is_main_frame && url.to_string() == "about:blank"
+    {
+      // `loadHTMLString:baseURL:` without a base URL loads the html from `about:blank`
+      (*handler).call((WKNavigationActionPolicy::Allow,));
     } else {
       let function = &this.ivars().navigation_policy_function;
       match function(url.to_string(), is_main_frame) {
@@ -91,6 +152,25 @@ pub(crate) fn navigation_policy_response(
   handler: &block2::Block<dyn Fn(WKNavigationResponsePolicy)>,
 ) {
   unsafe {
+    // HTTP errors are loaded as regular pages, so report them here
+    if let Some(navigation_failed_handler) = &this.ivars().navigation_failed_handler {
+      let url_response = response.response();
+      if response.isForMainFrame() && url_response.isKindOfClass(NSHTTPURLResponse::class()) {
+        let url = url_response
+          .URL()
+          .and_then(|url| url.absoluteString())
+          .map(|url| url.to_string())
+          .unwrap_or_default();
+        let status = Retained::cast::<NSHTTPURLResponse>(url_response).statusCode();
+        if status >= 400 {
+          navigation_failed_handler(NavigationError {
+            url,
+            kind: NavigationErrorKind::HttpStatus(status as u16),
+          });
+        }
+      }
+    }
+
     let can_show_mime_type = response.canShowMIMEType();
 
     if !can_show_mime_type {